@@ -13,6 +13,4 @@
 
 extern crate crossterm;
 
-use crossterm::cursor;
-
 fn main() {}
@@ -97,14 +97,11 @@ pub fn print_all_background_colors()
     println!("Dark Cyan : \t {}", paint("  ").on(Color::DarkCyan));
     println!("Grey : \t\t {}", paint("  ").on(Color::Grey));
     println!("White : \t {}", paint("  ").on(Color::White));
-    #[cfg(unix)]
     println!("RGB (10,10,10): \t {}", paint("  ").on(Color::Rgb {r: 10, g: 10, b: 10}));
-    #[cfg(unix)]
     println!("RGB (10,10,10): \t {}", paint("  ").on(Color::AnsiValue(50)));
 }
 
-/// Print font with all available attributes. Note that this can only be used at unix systems and that some are not supported widely | demonstration..
-#[cfg(unix)]
+/// Print font with all available attributes. Note that some are not supported widely | demonstration..
 pub fn print_font_with_attributes()
 {
     println!("{}", paint("Normal text"));
@@ -120,7 +117,6 @@ pub fn print_font_with_attributes()
 }
 
 /// Print all supported rgb colors  | demonstration.
-#[cfg(unix)]
 pub fn print_supported_colors()
 {   
     let count = crossterm::style::color().get_available_color_count().unwrap();
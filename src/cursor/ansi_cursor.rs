@@ -17,43 +17,44 @@ impl Construct for AnsiCursor {
 }
 
 impl ITerminalCursor for AnsiCursor {
-    fn goto(&self, x: u16, y: u16) {
-        let mut some_writer = io::stdout();
+    fn goto(&self, output: &mut dyn Write, x: u16, y: u16) -> io::Result<()> {
         // ANSI codes are one-based. I want 0 based so we just need to increment and x,y.
-        write!(&mut some_writer, csi!("{};{}H"), y + 1, x + 1);
+        write!(output, csi!("{};{}H"), y + 1, x + 1)
     }
 
-    fn pos(&self) -> (u16, u16) {
+    fn pos(&self) -> io::Result<(u16, u16)> {
         functions::get_cursor_position()
     }
 
-    fn move_up(&self, count: u16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("{}A"), count);
+    fn move_up(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        write!(output, csi!("{}A"), count)
     }
 
-    fn move_right(&self, count: u16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("{}C"), count);
+    fn move_right(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        write!(output, csi!("{}C"), count)
     }
 
-    fn move_down(&self, count: u16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("{}B"), count);
+    fn move_down(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        write!(output, csi!("{}B"), count)
     }
 
-    fn move_left(&self, count: u16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("{}D"), count);
+    fn move_left(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        write!(output, csi!("{}D"), count)
     }
 
-    fn save_position(&mut self) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("s"));
+    fn save_position(&mut self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, csi!("s"))
     }
 
-    fn reset_position(&self) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("u"));
+    fn reset_position(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, csi!("u"))
+    }
+
+    fn hide(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, csi!("?25l"))
+    }
+
+    fn show(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, csi!("?25h"))
     }
 }
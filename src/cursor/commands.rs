@@ -0,0 +1,189 @@
+//! This module contains the cursor commands that can be used with the `queue!` and `execute!` macros.
+
+use super::AnsiCursor;
+use super::ITerminalCursor;
+#[cfg(target_os = "windows")]
+use super::WinApiCursor;
+use state;
+use {OutputTarget, QueueableCommand};
+
+use std::io::{self, Write};
+
+/// Command that moves the cursor to the given position (x,y), the position is 0-based.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Goto(pub u16, pub u16);
+
+impl QueueableCommand for Goto {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.goto(output, self.0, self.1)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.goto(output, self.0, self.1)
+    }
+}
+
+/// Command that moves the cursor the given number of rows up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MoveUp(pub u16);
+
+impl QueueableCommand for MoveUp {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.move_up(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.move_up(output, self.0)
+    }
+}
+
+/// Command that moves the cursor the given number of rows down.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MoveDown(pub u16);
+
+impl QueueableCommand for MoveDown {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.move_down(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.move_down(output, self.0)
+    }
+}
+
+/// Command that moves the cursor the given number of columns to the left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MoveLeft(pub u16);
+
+impl QueueableCommand for MoveLeft {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.move_left(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.move_left(output, self.0)
+    }
+}
+
+/// Command that moves the cursor the given number of columns to the right.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MoveRight(pub u16);
+
+impl QueueableCommand for MoveRight {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.move_right(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.move_right(output, self.0)
+    }
+}
+
+/// Command that saves the current cursor position, it can be restored with `ResetPos`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SavePos;
+
+impl QueueableCommand for SavePos {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.save_position(output)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.save_position(output)
+    }
+}
+
+/// Command that moves the cursor back to the position that is saved with `SavePos`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ResetPos;
+
+impl QueueableCommand for ResetPos {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.reset_position(output)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiCursor.reset_position(output)
+    }
+}
+
+/// Command that hides the cursor.
+///
+/// The process only remembers that the cursor is hidden when it is written to the terminal.
+///
+/// #Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate crossterm;
+///
+/// use self::crossterm::cursor::Hide;
+/// use self::crossterm::Crossterm;
+///
+/// fn main() {
+///     let mut output = Vec::new();
+///     queue!(output, Hide).unwrap();
+///
+///     assert_eq!(output, b"\x1B[?25l");
+///     assert!(Crossterm::new().is_cursor_visible());
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hide;
+
+impl QueueableCommand for Hide {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.hide(output)
+    }
+
+    fn write_ansi_to(&self, output: &mut dyn Write, target: OutputTarget) -> io::Result<()> {
+        AnsiCursor.hide(output)?;
+
+        if target.is_tty {
+            state::set_cursor_visible(false);
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        // the `WINAPI` always hides the cursor of the console.
+        WinApiCursor.hide(output)?;
+        state::set_cursor_visible(false);
+        Ok(())
+    }
+}
+
+/// Command that shows the cursor after it is hidden with `Hide`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Show;
+
+impl QueueableCommand for Show {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiCursor.show(output)
+    }
+
+    fn write_ansi_to(&self, output: &mut dyn Write, target: OutputTarget) -> io::Result<()> {
+        AnsiCursor.show(output)?;
+
+        if target.is_tty {
+            state::set_cursor_visible(true);
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        // the `WINAPI` always shows the cursor of the console.
+        WinApiCursor.show(output)?;
+        state::set_cursor_visible(true);
+        Ok(())
+    }
+}
@@ -5,30 +5,100 @@
 
 use super::*;
 use shared::functions;
-use {Construct, Context};
+use state;
+use {Construct, OutputTarget, TerminalOutput};
 
 use std::fmt::Display;
-use std::ops::Drop;
+use std::io::{self, Stdout, Write};
 
 /// Struct that stores an specific platform implementation for cursor related actions.
-pub struct TerminalCursor {
-    terminal_cursor: Option<Box<ITerminalCursor>>,
+///
+/// The cursor actions are written to the output `W`, which is stdout by default.
+pub struct TerminalCursor<W: Write = Stdout> {
+    terminal_cursor: Option<Box<dyn ITerminalCursor>>,
+    output: W,
+    /// The output the actions are written to, hiding the cursor is only remembered when it is the terminal.
+    target: OutputTarget,
 }
 
-impl TerminalCursor {
+impl TerminalCursor<Stdout> {
     /// Create new cursor instance whereon cursor related actions can be performed.
-    pub fn new() -> TerminalCursor {
+    pub fn new() -> TerminalCursor<Stdout> {
         #[cfg(target_os = "windows")]
         let cursor =
-            functions::get_module::<Box<ITerminalCursor>>(WinApiCursor::new(), AnsiCursor::new());
+            functions::get_module::<Box<dyn ITerminalCursor>>(WinApiCursor::new(), AnsiCursor::new());
 
         #[cfg(not(target_os = "windows"))]
-        let cursor = Some(AnsiCursor::new() as Box<ITerminalCursor>);
+        let cursor = Some(AnsiCursor::new() as Box<dyn ITerminalCursor>);
 
         TerminalCursor {
             terminal_cursor: cursor,
+            output: io::stdout(),
+            target: OutputTarget::stdout(),
         }
     }
+}
+
+impl<W: Write + TerminalOutput> TerminalCursor<W> {
+    /// Create new cursor instance that writes the cursor actions to the given output,
+    /// like stderr, an file or an `Vec<u8>`.
+    ///
+    /// The actions are always written as `ANSI escape codes`, because the `WINAPI` can not write to an arbitrary output.
+    /// Note that `pos()` still asks the terminal the process runs in for the cursor position.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::cursor::TerminalCursor;
+    ///
+    /// let mut cursor = TerminalCursor::from_output(Vec::new());
+    /// cursor.goto(4, 2).unwrap().move_up(1).unwrap();
+    ///
+    /// assert_eq!(cursor.output(), b"\x1B[3;5H\x1B[1A");
+    ///
+    /// ```
+    pub fn from_output(output: W) -> TerminalCursor<W> {
+        TerminalCursor {
+            terminal_cursor: Some(AnsiCursor::new() as Box<dyn ITerminalCursor>),
+            target: OutputTarget::of(&output),
+            output,
+        }
+    }
+}
+
+impl<W: Write> TerminalCursor<W> {
+    /// Create new cursor instance that performs the cursor actions with the given implementation,
+    /// for example the `VirtualTerminal` in tests.
+    ///
+    /// The implementation decides where the actions go, so hiding the cursor is not remembered by the process.
+    pub fn with_implementation(cursor: Box<dyn ITerminalCursor>, output: W) -> TerminalCursor<W> {
+        TerminalCursor {
+            terminal_cursor: Some(cursor),
+            output,
+            target: OutputTarget {
+                is_tty: false,
+                is_stdout: false,
+            },
+        }
+    }
+
+    /// Get a reference to the output the cursor actions are written to.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Get a mutable reference to the output the cursor actions are written to.
+    pub fn output_mut(&mut self) -> &mut W {
+        &mut self.output
+    }
+
+    /// Consume the cursor and get the output the cursor actions are written to.
+    pub fn into_output(self) -> W {
+        self.output
+    }
 
     /// Goto some position (x,y) in the terminal.
     ///
@@ -40,14 +110,15 @@ impl TerminalCursor {
     ///
     /// use self::crossterm::cursor;
     ///     
-    /// cursor::cursor().goto(10,10);
+    /// cursor::cursor().goto(10,10).unwrap();
     ///
     /// ```
-    pub fn goto(&mut self, x: u16, y: u16) -> &mut TerminalCursor {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.goto(x, y);
+    pub fn goto(&mut self, x: u16, y: u16) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.goto(&mut self.output, x, y)?,
+            None => return Err(functions::not_supported()),
         }
-        self
+        Ok(self)
     }
 
     /// Get current cursor position (x,y) in the terminal.
@@ -60,15 +131,16 @@ impl TerminalCursor {
     ///
     /// use self::crossterm::cursor;
     ///          
-    /// let pos = cursor::cursor().pos();
-    /// println!("{:?}", pos);
+    /// match cursor::cursor().pos() {
+    ///     Ok((x, y)) => println!("The cursor is at: {}, {}", x, y),
+    ///     Err(e) => println!("The cursor position is not available: {}", e),
+    /// }
     ///
     /// ```
-    pub fn pos(&mut self) -> (u16, u16) {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.pos()
-        } else {
-            (0, 0)
+    pub fn pos(&mut self) -> io::Result<(u16, u16)> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.pos(),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -83,17 +155,18 @@ impl TerminalCursor {
     /// use self::crossterm::cursor;
     ///      
     /// // Move 1 time up
-    /// cursor::cursor().move_up(1);
+    /// cursor::cursor().move_up(1).unwrap();
     ///
     /// // Move 2 times up
-    /// cursor::cursor().move_up(2);
+    /// cursor::cursor().move_up(2).unwrap();
     ///
     /// ```
-    pub fn move_up(&mut self, count: u16) -> &mut TerminalCursor {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.move_up(count);
+    pub fn move_up(&mut self, count: u16) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.move_up(&mut self.output, count)?,
+            None => return Err(functions::not_supported()),
         }
-        self
+        Ok(self)
     }
 
     /// Move the current cursor position `n` times right.
@@ -108,17 +181,18 @@ impl TerminalCursor {
     ///
     ///
     /// // move 1 time right
-    /// cursor::cursor().move_right(1);
+    /// cursor::cursor().move_right(1).unwrap();
     ///
     /// // move 2 times right
-    /// cursor::cursor().move_right(2);
+    /// cursor::cursor().move_right(2).unwrap();
     ///
     /// ```
-    pub fn move_right(&mut self, count: u16) -> &mut TerminalCursor {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.move_right(count);
+    pub fn move_right(&mut self, count: u16) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.move_right(&mut self.output, count)?,
+            None => return Err(functions::not_supported()),
         }
-        self
+        Ok(self)
     }
 
     /// Move the current cursor position `n` times down.
@@ -132,17 +206,18 @@ impl TerminalCursor {
     /// use self::crossterm::cursor;
     ///
     /// // move 1 time down
-    /// cursor::cursor().move_down(1);
+    /// cursor::cursor().move_down(1).unwrap();
     ///
     /// // move 2 times down
-    /// cursor::cursor().move_down(2);
+    /// cursor::cursor().move_down(2).unwrap();
     ///
     /// ```
-    pub fn move_down(&mut self, count: u16) -> &mut TerminalCursor {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.move_down(count);
+    pub fn move_down(&mut self, count: u16) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.move_down(&mut self.output, count)?,
+            None => return Err(functions::not_supported()),
         }
-        self
+        Ok(self)
     }
 
     /// Move the current cursor position `n` times left.
@@ -156,22 +231,23 @@ impl TerminalCursor {
     /// use self::crossterm::cursor;
     ///
     /// // move 1 time left
-    /// cursor::cursor().move_left(1);
+    /// cursor::cursor().move_left(1).unwrap();
     ///
     /// // move 2 time left
-    /// cursor::cursor().move_left(2);
+    /// cursor::cursor().move_left(2).unwrap();
     ///
     /// ```
-    pub fn move_left(&mut self, count: u16) -> &mut TerminalCursor {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.move_left(count);
+    pub fn move_left(&mut self, count: u16) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.move_left(&mut self.output, count)?,
+            None => return Err(functions::not_supported()),
         }
-        self
+        Ok(self)
     }
 
     /// Print an value at the current cursor position.
     ///
-    /// This method prints an value with `print!()` and clears the buffer afterwards.
+    /// This method writes an value to the output and flushes the buffer afterwards.
     /// Rust's standard output is line-buffered. So your text gets sent to the console one line at a time.
     /// If you set the curosr position and try to `print!()` at that position and do not clear the buffer, than the character will not be printed at that position.
     /// But will be printed when the next `println()` will be done.
@@ -191,23 +267,21 @@ impl TerminalCursor {
     /// use std::io::Write;
     ///
     /// // of course we can just do this.
-    /// cursor::cursor().goto(10,10);
+    /// cursor::cursor().goto(10,10).unwrap();
     /// print!("@");
-    /// std::io::stdout().flush();
+    /// std::io::stdout().flush().unwrap();
     ///
     /// // but now we can chain the methods so it looks cleaner and it automatically flushes the buffer.  
     /// cursor::cursor()
-    /// .goto(10,10)
-    /// .print("@");
+    /// .goto(10,10).unwrap()
+    /// .print("@").unwrap();
     ///
     /// ```
-    pub fn print<D: Display>(&mut self, value: D) -> &mut TerminalCursor {
-        print!("{}", value);
-        use std;
-        use std::io::Write;
+    pub fn print<D: Display>(&mut self, value: D) -> io::Result<&mut TerminalCursor<W>> {
+        write!(self.output, "{}", value)?;
         // rust is line buffered so we need to flush the buffer in order to print it at the current cursor position.
-        std::io::stdout().flush();
-        self
+        self.output.flush()?;
+        Ok(self)
     }
 
     /// Save cursor position for recall later.
@@ -222,12 +296,13 @@ impl TerminalCursor {
     ///
     /// use self::crossterm::cursor;
     ///
-    /// cursor::cursor().safe_position();
+    /// cursor::cursor().save_position().unwrap();
     ///
     /// ```
-    pub fn save_position(&mut self) {
-        if let Some(ref mut terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.save_position();
+    pub fn save_position(&mut self) -> io::Result<()> {
+        match self.terminal_cursor {
+            Some(ref mut terminal_cursor) => terminal_cursor.save_position(&mut self.output),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -243,14 +318,71 @@ impl TerminalCursor {
     ///
     /// use self::crossterm::cursor::cursor;
     ///
-    /// cursor().reset_position();
+    /// cursor().reset_position().unwrap();
     ///
     /// ```
-    pub fn reset_position(&mut self) {
-        if let Some(ref terminal_cursor) = self.terminal_cursor {
-            terminal_cursor.reset_position();
+    pub fn reset_position(&mut self) -> io::Result<()> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.reset_position(&mut self.output),
+            None => Err(functions::not_supported()),
         }
     }
+
+    /// Hide the cursor, it stays hidden until `show()` is called.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::cursor::cursor;
+    ///
+    /// cursor().hide().unwrap();
+    ///
+    /// ```
+    pub fn hide(&mut self) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.hide(&mut self.output)?,
+            None => return Err(functions::not_supported()),
+        }
+
+        if self.target.is_tty {
+            state::set_cursor_visible(false);
+        }
+        Ok(self)
+    }
+
+    /// Show the cursor after it is hidden with `hide()`.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::cursor::cursor;
+    ///
+    /// cursor().show().unwrap();
+    ///
+    /// ```
+    pub fn show(&mut self) -> io::Result<&mut TerminalCursor<W>> {
+        match self.terminal_cursor {
+            Some(ref terminal_cursor) => terminal_cursor.show(&mut self.output)?,
+            None => return Err(functions::not_supported()),
+        }
+
+        if self.target.is_tty {
+            state::set_cursor_visible(true);
+        }
+        Ok(self)
+    }
+}
+
+impl Default for TerminalCursor<Stdout> {
+    fn default() -> TerminalCursor<Stdout> {
+        TerminalCursor::new()
+    }
 }
 
 /// Get an TerminalCursor implementation whereon cursor related actions can be performed.
@@ -267,10 +399,10 @@ impl TerminalCursor {
 ///
 /// // Get cursor and goto pos X: 5, Y: 10
 /// let mut cursor = cursor::cursor();
-/// cursor.goto(5,10);
+/// cursor.goto(5,10).unwrap();
 ///     
 /// //Or you can do it in one line.
-/// cursor::cursor().goto(5,10);
+/// cursor::cursor().goto(5,10).unwrap();
 ///
 /// ```
 pub fn cursor() -> Box<TerminalCursor> {
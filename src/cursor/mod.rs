@@ -9,6 +9,8 @@
 //!
 
 mod ansi_cursor;
+mod commands;
+#[allow(clippy::module_inception)]
 mod cursor;
 #[cfg(target_os = "windows")]
 mod winapi_cursor;
@@ -17,31 +19,43 @@ use self::ansi_cursor::AnsiCursor;
 #[cfg(target_os = "windows")]
 use self::winapi_cursor::WinApiCursor;
 
+pub use self::commands::{
+    Goto, Hide, MoveDown, MoveLeft, MoveRight, MoveUp, ResetPos, SavePos, Show,
+};
 pub use self::cursor::{cursor, TerminalCursor};
 
-///! This trait defines the actions that can be preformed with the terminal cursor.
-///! This trait can be implemented so that an concrete implementation of the ITerminalCursor can forfill
-///! the wishes to work on an specific platform.
-///!
-///! ## For example:
-///!
-///! This trait is implemented for `WINAPI` (Windows specific) and `ANSI` (Unix specific),
-///! so that cursor related actions can be preformed on both unix and windows systems.
+use std::io::{self, Write};
+
+/// This trait defines the actions that can be preformed with the terminal cursor.
+/// This trait can be implemented so that an concrete implementation of the ITerminalCursor can forfill
+/// the wishes to work on an specific platform.
+///
+/// ## For example:
+///
+/// This trait is implemented for `WINAPI` (Windows specific) and `ANSI` (Unix specific),
+/// so that cursor related actions can be preformed on both unix and windows systems.
+///
+/// The actions are written to the given output, implementations that do not write to the output flush it first
+/// so that everything that is written before the action is shown at the right position.
 pub trait ITerminalCursor {
     /// Goto some location (x,y) in the terminal.
-    fn goto(&self, x: u16, y: u16);
+    fn goto(&self, output: &mut dyn Write, x: u16, y: u16) -> io::Result<()>;
     /// Get the location (x,y) of the current curor in the terminal
-    fn pos(&self) -> (u16, u16);
+    fn pos(&self) -> io::Result<(u16, u16)>;
     /// Move cursor n times up
-    fn move_up(&self, count: u16);
+    fn move_up(&self, output: &mut dyn Write, count: u16) -> io::Result<()>;
     /// Move the cursor `n` times to the right.
-    fn move_right(&self, count: u16);
+    fn move_right(&self, output: &mut dyn Write, count: u16) -> io::Result<()>;
     /// Move the cursor `n` times down.
-    fn move_down(&self, count: u16);
+    fn move_down(&self, output: &mut dyn Write, count: u16) -> io::Result<()>;
     /// Move the cursor `n` times left.
-    fn move_left(&self, count: u16);
+    fn move_left(&self, output: &mut dyn Write, count: u16) -> io::Result<()>;
     /// Save cursor position for recall later. Note that this position is stored program based not per instance of the cursor struct.
-    fn save_position(&mut self);
+    fn save_position(&mut self, output: &mut dyn Write) -> io::Result<()>;
     /// Return to saved cursor position
-    fn reset_position(&self);
+    fn reset_position(&self, output: &mut dyn Write) -> io::Result<()>;
+    /// Hide the cursor.
+    fn hide(&self, output: &mut dyn Write) -> io::Result<()>;
+    /// Show the cursor.
+    fn show(&self, output: &mut dyn Write) -> io::Result<()>;
 }
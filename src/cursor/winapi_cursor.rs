@@ -5,6 +5,8 @@ use super::ITerminalCursor;
 use kernel::windows_kernel::{cursor, kernel};
 use Construct;
 
+use std::io::{self, Write};
+
 /// This struct is an windows implementation for cursor related actions.
 pub struct WinApiCursor;
 
@@ -15,42 +17,59 @@ impl Construct for WinApiCursor {
 }
 
 impl ITerminalCursor for WinApiCursor {
-    fn goto(&self, x: u16, y: u16) {
-        kernel::set_console_cursor_position(x as i16, y as i16);
+    fn goto(&self, output: &mut dyn Write, x: u16, y: u16) -> io::Result<()> {
+        output.flush()?;
+        kernel::set_console_cursor_position(x as i16, y as i16)
     }
 
-    fn pos(&self) -> (u16, u16) {
+    fn pos(&self) -> io::Result<(u16, u16)> {
         cursor::pos()
     }
 
-    fn move_up(&self, count: u16) {
-        let (xpos, ypos) = self.pos();
-        self.goto(xpos, ypos - count);
+    fn move_up(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        output.flush()?;
+        let (xpos, ypos) = self.pos()?;
+        self.goto(output, xpos, ypos.saturating_sub(count))
     }
 
-    fn move_right(&self, count: u16) {
-        let (xpos, ypos) = self.pos();
+    fn move_right(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        output.flush()?;
+        let (xpos, ypos) = self.pos()?;
 
-        self.goto(xpos + count, ypos);
+        self.goto(output, xpos.saturating_add(count), ypos)
     }
 
-    fn move_down(&self, count: u16) {
-        let (xpos, ypos) = self.pos();
+    fn move_down(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        output.flush()?;
+        let (xpos, ypos) = self.pos()?;
 
-        self.goto(xpos, ypos + count);
+        self.goto(output, xpos, ypos.saturating_add(count))
     }
 
-    fn move_left(&self, count: u16) {
-        let (xpos, ypos) = self.pos();
+    fn move_left(&self, output: &mut dyn Write, count: u16) -> io::Result<()> {
+        output.flush()?;
+        let (xpos, ypos) = self.pos()?;
+
+        self.goto(output, xpos.saturating_sub(count), ypos)
+    }
+
+    fn save_position(&mut self, output: &mut dyn Write) -> io::Result<()> {
+        output.flush()?;
+        cursor::save_cursor_pos()
+    }
 
-        self.goto(xpos - count, ypos);
+    fn reset_position(&self, output: &mut dyn Write) -> io::Result<()> {
+        output.flush()?;
+        cursor::reset_to_saved_position()
     }
 
-    fn save_position(&mut self) {
-        cursor::save_cursor_pos();
+    fn hide(&self, output: &mut dyn Write) -> io::Result<()> {
+        output.flush()?;
+        kernel::set_console_cursor_visible(false)
     }
 
-    fn reset_position(&self) {
-        cursor::reset_to_saved_position();
+    fn show(&self, output: &mut dyn Write) -> io::Result<()> {
+        output.flush()?;
+        kernel::set_console_cursor_visible(true)
     }
 }
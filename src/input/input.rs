@@ -0,0 +1,281 @@
+//! With this module you can perform actions that are input related.
+//! Like reading the keys that are pressed by the user.
+
+use super::*;
+use shared::functions;
+use state::commands::{EnableMouseModeCommand, IContextCommand};
+use state::StateKey;
+use {Construct, Context};
+
+use std::io;
+use std::time::Duration;
+
+/// Struct that stores an specific platform implementation for input related actions.
+pub struct TerminalInput {
+    terminal_input: Option<Box<dyn ITerminalInput>>,
+    /// The key of the mouse capture change in the context when mouse capture is enabled.
+    mouse_mode_key: Option<StateKey>,
+}
+
+impl TerminalInput {
+    /// Create new input instance whereon input related actions can be performed.
+    pub fn new() -> TerminalInput {
+        #[cfg(target_os = "windows")]
+        let input = Some(WinApiInput::new() as Box<dyn ITerminalInput>);
+
+        #[cfg(not(target_os = "windows"))]
+        let input = Some(UnixInput::new() as Box<dyn ITerminalInput>);
+
+        TerminalInput {
+            terminal_input: input,
+            mouse_mode_key: None,
+        }
+    }
+
+    /// Wait until an event is available or until the given timeout elapsed.
+    ///
+    /// Returns `true` when an event is available, the event can then be read with `read()` without blocking.
+    /// Use an timeout of zero to check if an event is available without waiting.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::input::{input, Event, KeyEvent};
+    /// use std::time::Duration;
+    ///
+    /// let mut input = input();
+    ///
+    /// loop {
+    ///     // wait at most 500 milliseconds for the user to press a key.
+    ///     if input.poll(Duration::from_millis(500)).unwrap() {
+    ///         match input.read().unwrap() {
+    ///             Event::Key(KeyEvent::Char('q')) => break,
+    ///             event => println!("{:?}", event),
+    ///         }
+    ///     } else {
+    ///         // no event within 500 milliseconds, redraw the screen.
+    ///     }
+    /// }
+    ///
+    /// ```
+    pub fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        if let Some(ref mut terminal_input) = self.terminal_input {
+            terminal_input.poll(timeout)
+        } else {
+            Err(functions::not_supported())
+        }
+    }
+
+    /// Read the next event, this call blocks until an event is available.
+    ///
+    /// Use `poll()` to check if an event is available first if you do not want to block.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::input::{input, Event};
+    ///
+    /// match input().read() {
+    ///     Ok(Event::Key(key)) => println!("Pressed: {:?}", key),
+    ///     Ok(Event::Mouse(mouse)) => println!("Mouse: {:?}", mouse),
+    ///     Ok(Event::Resize(width, height)) => println!("Resized to: {}, {}", width, height),
+    ///     Ok(Event::Redraw) => println!("Continued, everything should be drawn again"),
+    ///     Err(e) => println!("Error: {}", e),
+    /// }
+    ///
+    /// ```
+    pub fn read(&mut self) -> io::Result<Event> {
+        if let Some(ref mut terminal_input) = self.terminal_input {
+            terminal_input.read()
+        } else {
+            Err(functions::not_supported())
+        }
+    }
+
+    /// Read the next key that is pressed by the user, this call blocks until a key is pressed.
+    ///
+    /// Other events, like mouse events, are skipped.
+    ///
+    /// Note that the terminal should be in raw mode, otherwise the input will only be available after the user pressed enter.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::input::{input, KeyEvent};
+    /// use self::crossterm::terminal::raw::IntoRawMode;
+    /// use self::crossterm::Context;
+    /// use std::io::stdout;
+    ///
+    /// let mut context = Context::new();
+    /// let _raw = stdout().into_raw_mode(&mut context).unwrap();
+    ///
+    /// match input().read_key() {
+    ///     Ok(KeyEvent::Char(c)) => println!("Pressed: {}", c),
+    ///     Ok(KeyEvent::Ctrl('c')) => println!("Pressed: ctrl + c"),
+    ///     Ok(key) => println!("Pressed: {:?}", key),
+    ///     Err(e) => println!("Error: {}", e),
+    /// }
+    ///
+    /// ```
+    pub fn read_key(&mut self) -> io::Result<KeyEvent> {
+        loop {
+            if let Event::Key(key) = self.read()? {
+                return Ok(key);
+            }
+        }
+    }
+
+    /// Enable mouse capture, after this the mouse actions are reported as `Event::Mouse` by `read()`.
+    ///
+    /// Mouse capture is registered as change in the given context,
+    /// so it will be disabled when the changes of the context are restored for example when an `RawTerminal` is dropped.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::input::{input, Event, MouseEventKind};
+    /// use self::crossterm::terminal::raw::IntoRawMode;
+    /// use self::crossterm::Context;
+    /// use std::io::stdout;
+    ///
+    /// let mut context = Context::new();
+    /// let mut input = input();
+    ///
+    /// input.enable_mouse_mode(&mut context).unwrap();
+    /// let _raw = stdout().into_raw_mode(&mut context).unwrap();
+    ///
+    /// if let Ok(Event::Mouse(mouse)) = input.read() {
+    ///     if mouse.kind == MouseEventKind::Press {
+    ///         println!("Clicked at: {}, {}", mouse.x, mouse.y);
+    ///     }
+    /// }
+    ///
+    /// ```
+    pub fn enable_mouse_mode(&mut self, context: &mut Context) -> io::Result<()> {
+        if self.mouse_mode_key.is_some() {
+            return Ok(());
+        }
+
+        let (mut command, key) = EnableMouseModeCommand::new(context);
+
+        match command.execute() {
+            Ok(()) => {
+                self.mouse_mode_key = Some(key);
+                Ok(())
+            }
+            Err(e) => {
+                // the mouse mode is not enabled, so it should not be disabled when the context is restored.
+                let _ = context.undo_state(key);
+                Err(e)
+            }
+        }
+    }
+
+    /// Disable mouse capture that is enabled with `enable_mouse_mode()` and remove it from the given context.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::input::input;
+    /// use self::crossterm::Context;
+    ///
+    /// let mut context = Context::new();
+    /// let mut input = input();
+    ///
+    /// input.enable_mouse_mode(&mut context).unwrap();
+    /// // read some mouse events.
+    /// input.disable_mouse_mode(&mut context).unwrap();
+    ///
+    /// ```
+    pub fn disable_mouse_mode(&mut self, context: &mut Context) -> io::Result<()> {
+        match self.mouse_mode_key.take() {
+            Some(key) => context.undo_state(key),
+            None => Ok(()),
+        }
+    }
+
+    /// Get an iterator over the keys pressed by the user.
+    ///
+    /// The iterator blocks until the next key is pressed and ends when the input is closed.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    ///
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::input::{input, KeyEvent};
+    ///
+    /// let mut input = input();
+    ///
+    /// for key in input.keys() {
+    ///     match key {
+    ///         Ok(KeyEvent::Char('q')) => break,
+    ///         Ok(key) => println!("Pressed: {:?}", key),
+    ///         Err(e) => println!("Error: {}", e),
+    ///     }
+    /// }
+    ///
+    /// ```
+    pub fn keys(&mut self) -> Keys<'_> {
+        Keys { input: self }
+    }
+}
+
+impl Default for TerminalInput {
+    fn default() -> TerminalInput {
+        TerminalInput::new()
+    }
+}
+
+/// An iterator over the keys pressed by the user, see `TerminalInput::keys()`.
+pub struct Keys<'a> {
+    input: &'a mut TerminalInput,
+}
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = io::Result<KeyEvent>;
+
+    fn next(&mut self) -> Option<io::Result<KeyEvent>> {
+        match self.input.read_key() {
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => None,
+            key => Some(key),
+        }
+    }
+}
+
+/// Get an TerminalInput implementation whereon input related actions can be performed.
+///
+/// #Example
+///
+/// ```rust,no_run
+///
+/// extern crate crossterm;
+///
+/// use self::crossterm::input;
+///
+/// let mut input = input::input();
+///
+/// // wait for the user to press a key.
+/// let key = input.read_key();
+/// println!("{:?}", key);
+///
+/// ```
+pub fn input() -> Box<TerminalInput> {
+    Box::from(TerminalInput::new())
+}
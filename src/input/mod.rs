@@ -0,0 +1,155 @@
+//! With this module you can read the keys the user presses in the terminal.
+//!
+//! The input is read from stdin and the raw bytes the terminal sends are decoded into an `Event`.
+//! With `TerminalInput::poll()` you can wait for input with a timeout so that you can do other work, like redrawing the screen, while waiting for the user.
+//! Note that the terminal should be in raw mode while reading, otherwise the input is line buffered and echoed to the screen.
+//! See `terminal::raw` for switching to raw mode.
+//!
+//! Mouse events are only reported after mouse capture is enabled with `TerminalInput::enable_mouse_mode()`.
+
+#[allow(clippy::module_inception)]
+mod input;
+
+#[cfg(not(target_os = "windows"))]
+mod unix_input;
+#[cfg(target_os = "windows")]
+mod winapi_input;
+
+#[cfg(not(target_os = "windows"))]
+use self::unix_input::UnixInput;
+#[cfg(target_os = "windows")]
+use self::winapi_input::WinApiInput;
+
+pub use self::input::{input, Keys, TerminalInput};
+
+use std::io;
+use std::time::Duration;
+
+/// Enum that represents the events that can be read from the input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// An key is pressed.
+    Key(KeyEvent),
+    /// An mouse action is performed, only reported when mouse capture is enabled.
+    Mouse(MouseEvent),
+    /// The terminal is resized, the new size is the same as `Terminal::terminal_size()` returns.
+    /// This is only reported on unix systems.
+    Resize(u16, u16),
+    /// The process is continued after it was stopped, for example after it is suspended with ctrl + z and brought back with `fg`.
+    ///
+    /// Other programs may have written to the terminal in the meantime, so everything should be drawn again.
+    /// This is only reported on unix systems.
+    Redraw,
+}
+
+/// Struct that represents an mouse action.
+///
+/// Note that the position is 0-based like all crossterm positions, so the top left cell is (0, 0).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    /// The kind of mouse action.
+    pub kind: MouseEventKind,
+    /// The button that is pressed, released or dragged, `None` when no button is involved or when the terminal did not report it.
+    pub button: Option<MouseButton>,
+    /// The column of the mouse cursor.
+    pub x: u16,
+    /// The row of the mouse cursor.
+    pub y: u16,
+    /// The modifier keys that were held down during the mouse action.
+    pub modifiers: KeyModifiers,
+}
+
+/// Enum that represents the kind of mouse action.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    /// An mouse button is pressed.
+    Press,
+    /// An mouse button is released.
+    Release,
+    /// The mouse is moved while an button is held down.
+    Drag,
+    /// The mouse is moved without any button held down.
+    Move,
+    /// The mouse wheel is scrolled up.
+    ScrollUp,
+    /// The mouse wheel is scrolled down.
+    ScrollDown,
+}
+
+/// Enum that represents an mouse button.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Struct that represents the modifier keys that were held down.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+/// Enum that represents the keys that can be pressed by the user.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KeyEvent {
+    /// Backspace key.
+    Backspace,
+    /// Enter key.
+    Enter,
+    /// Left arrow key.
+    Left,
+    /// Right arrow key.
+    Right,
+    /// Up arrow key.
+    Up,
+    /// Down arrow key.
+    Down,
+    /// Home key.
+    Home,
+    /// End key.
+    End,
+    /// Page up key.
+    PageUp,
+    /// Page down key.
+    PageDown,
+    /// Tab key.
+    Tab,
+    /// Shift + Tab key.
+    BackTab,
+    /// Delete key.
+    Delete,
+    /// Insert key.
+    Insert,
+    /// Function key, `F(1)` up to and including `F(12)`.
+    F(u8),
+    /// Normal character.
+    Char(char),
+    /// Alt modified character.
+    Alt(char),
+    /// Ctrl modified character.
+    ///
+    /// Note that a few control characters have their own variant, like `Tab` (Ctrl + I) and `Enter` (Ctrl + M).
+    Ctrl(char),
+    /// Null byte (Ctrl + Space).
+    Null,
+    /// Escape key.
+    Esc,
+}
+
+/// This trait defines the actions that can be preformed with the terminal input.
+/// This trait can be implemented so that an concrete implementation of the ITerminalInput can forfill
+/// the wishes to work on an specific platform.
+///
+/// ## For example:
+///
+/// This trait is implemented for `WINAPI` (Windows specific) and `UNIX` (Unix specific),
+/// so that input related actions can be preformed on both unix and windows systems.
+pub trait ITerminalInput {
+    /// Wait until an event is available or until the given timeout elapsed, returns whether an event is available.
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+    /// Read the next event from the input, this call blocks until an event is available.
+    fn read(&mut self) -> io::Result<Event>;
+}
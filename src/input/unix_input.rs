@@ -0,0 +1,518 @@
+//! This is an `UNIX` specific implementation for input related action.
+//!
+//! The terminal sends the pressed keys as bytes to stdin, special keys like the arrow keys are send as `ANSI escape codes`.
+//! When mouse capture is enabled the mouse actions are send as escape codes too, both the `SGR` (`ESC [ < b ; x ; y M`) and the `X10` (`ESC [ M b x y`) encoding are supported.
+//! This module reads those bytes and decodes them into an `Event`.
+//!
+//! When the terminal is resized the process receives `SIGWINCH`, this is reported as `Event::Resize`.
+//! When the process is continued after it was stopped it receives `SIGCONT`, this is reported as `Event::Redraw`.
+
+use super::{
+    Event, ITerminalInput, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use kernel::unix_kernel::signal::SignalPipe;
+use kernel::unix_kernel::{input, terminal};
+use libc::{SIGCONT, SIGWINCH, STDIN_FILENO};
+use {Construct, Error, ErrorKind};
+
+use std::time::{Duration, Instant};
+use std::{io, str};
+
+/// This struct is an unix implementation for input related actions.
+pub struct UnixInput {
+    /// Bytes that are read from stdin but are not yet decoded into a key.
+    buffer: Vec<u8>,
+    /// Pipe that becomes readable when the terminal is resized, `None` when the signal handler could not be installed.
+    resize: Option<SignalPipe>,
+    /// Pipe that becomes readable when the process is continued, `None` when the signal handler could not be installed.
+    resume: Option<SignalPipe>,
+}
+
+impl Construct for UnixInput {
+    fn new() -> Box<UnixInput> {
+        Box::from(UnixInput {
+            buffer: Vec::new(),
+            resize: SignalPipe::new(SIGWINCH).ok(),
+            resume: SignalPipe::new(SIGCONT).ok(),
+        })
+    }
+}
+
+impl UnixInput {
+    /// Check if the buffer contains enough bytes to return an event without reading stdin.
+    fn has_buffered_event(&self) -> bool {
+        !self.buffer.is_empty() && !matches!(decode(&self.buffer), Decoded::Incomplete)
+    }
+
+    /// Wait until stdin can be read or an signal is received, returns whether stdin can be read and whether an signal is received.
+    fn poll_input(&self, timeout: Option<Duration>) -> io::Result<(bool, bool)> {
+        let mut fds = vec![STDIN_FILENO];
+        fds.extend(
+            self.resize
+                .iter()
+                .chain(self.resume.iter())
+                .map(SignalPipe::fd),
+        );
+
+        let ready = input::poll_fds(&fds, timeout)?;
+        Ok((ready[0], ready[1..].iter().any(|&ready| ready)))
+    }
+
+    /// Check if the terminal is resized since the last check.
+    fn take_resize(&mut self) -> bool {
+        match self.resize {
+            Some(ref mut resize) => resize.drain(),
+            None => false,
+        }
+    }
+
+    /// Read the bytes that are available on stdin into the buffer, returns the number of bytes that are read, `0` means the input is closed.
+    fn fill_buffer(&mut self) -> io::Result<usize> {
+        let mut buf = [0u8; 1024];
+        let read = input::read_stdin(&mut buf)?;
+
+        self.buffer.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+
+    /// Check if the process is continued since the last check.
+    fn take_resume(&mut self) -> bool {
+        match self.resume {
+            Some(ref mut resume) => resume.drain(),
+            None => false,
+        }
+    }
+}
+
+impl ITerminalInput for UnixInput {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        let deadline = Instant::now() + timeout;
+
+        // The available bytes are read into the buffer until they form an complete event,
+        // otherwise `read()` would block on the rest of an escape sequence after this returned `true`.
+        loop {
+            if self.has_buffered_event() {
+                return Ok(true);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let (stdin_ready, signaled) = self.poll_input(Some(remaining))?;
+
+            if signaled {
+                return Ok(true);
+            }
+
+            if !stdin_ready {
+                return Ok(false);
+            }
+
+            // `read()` reports that the input is closed without blocking.
+            if self.fill_buffer()? == 0 {
+                return Ok(true);
+            }
+        }
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        loop {
+            if !self.buffer.is_empty() {
+                match decode(&self.buffer) {
+                    Decoded::Key(key, len) => {
+                        self.buffer.drain(..len);
+                        return Ok(Event::Key(key));
+                    }
+                    Decoded::Mouse(mouse, len) => {
+                        self.buffer.drain(..len);
+                        return Ok(Event::Mouse(mouse));
+                    }
+                    Decoded::Invalid(len) => {
+                        self.buffer.drain(..len);
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            "could not decode the input into an event",
+                        ).into());
+                    }
+                    Decoded::Unsupported(len) => {
+                        self.buffer.drain(..len);
+                        return Err(Error::new(
+                            ErrorKind::Unsupported,
+                            "the key can not be reported as an event",
+                        ).into());
+                    }
+                    Decoded::Incomplete => {}
+                }
+            }
+
+            let (stdin_ready, signaled) = self.poll_input(None)?;
+
+            if signaled && self.take_resume() {
+                return Ok(Event::Redraw);
+            }
+
+            if signaled && self.take_resize() {
+                let (width, height) = terminal::terminal_size()?;
+                return Ok(Event::Resize(width, height));
+            }
+
+            if !stdin_ready {
+                continue;
+            }
+
+            if self.fill_buffer()? == 0 {
+                return Err(Error::new(ErrorKind::InputClosed, "the input is closed").into());
+            }
+        }
+    }
+}
+
+/// The result of decoding the bytes at the start of the input buffer.
+#[derive(Debug, PartialEq)]
+enum Decoded {
+    /// An key is decoded from the given number of bytes.
+    Key(KeyEvent, usize),
+    /// An mouse event is decoded from the given number of bytes.
+    Mouse(MouseEvent, usize),
+    /// An key that can not be reported as an `KeyEvent` is decoded from the given number of bytes,
+    /// like an arrow key with modifiers.
+    Unsupported(usize),
+    /// More bytes are needed to decode an key.
+    Incomplete,
+    /// The given number of bytes could not be decoded into an key.
+    Invalid(usize),
+}
+
+/// Decode the first key in the given bytes.
+fn decode(buf: &[u8]) -> Decoded {
+    match buf[0] {
+        b'\x1B' => decode_escape(buf),
+        b'\r' | b'\n' => Decoded::Key(KeyEvent::Enter, 1),
+        b'\t' => Decoded::Key(KeyEvent::Tab, 1),
+        b'\x7F' | b'\x08' => Decoded::Key(KeyEvent::Backspace, 1),
+        b'\0' => Decoded::Key(KeyEvent::Null, 1),
+        c @ b'\x01'..=b'\x1A' => Decoded::Key(KeyEvent::Ctrl((c - 0x1 + b'a') as char), 1),
+        c @ b'\x1C'..=b'\x1F' => Decoded::Key(KeyEvent::Ctrl((c - 0x1C + b'4') as char), 1),
+        _ => decode_char(buf),
+    }
+}
+
+/// Decode an key that starts with the escape byte.
+fn decode_escape(buf: &[u8]) -> Decoded {
+    // The terminal sends an complete escape sequence at once,
+    // so when the escape byte comes alone the user pressed the escape key.
+    if buf.len() == 1 {
+        return Decoded::Key(KeyEvent::Esc, 1);
+    }
+
+    match buf[1] {
+        b'\x1B' => Decoded::Key(KeyEvent::Esc, 1),
+        // the rest of the sequence is not read yet, the escape byte and `[` are only send together as the start of an sequence.
+        b'[' if buf.len() == 2 => Decoded::Incomplete,
+        b'[' => decode_csi(buf),
+        b'O' if buf.len() > 2 => match buf[2] {
+            b'A' => Decoded::Key(KeyEvent::Up, 3),
+            b'B' => Decoded::Key(KeyEvent::Down, 3),
+            b'C' => Decoded::Key(KeyEvent::Right, 3),
+            b'D' => Decoded::Key(KeyEvent::Left, 3),
+            b'H' => Decoded::Key(KeyEvent::Home, 3),
+            b'F' => Decoded::Key(KeyEvent::End, 3),
+            c @ b'P'..=b'S' => Decoded::Key(KeyEvent::F(1 + c - b'P'), 3),
+            _ => Decoded::Invalid(3),
+        },
+        _ => match decode_char(&buf[1..]) {
+            Decoded::Key(KeyEvent::Char(c), len) => Decoded::Key(KeyEvent::Alt(c), len + 1),
+            Decoded::Invalid(len) => Decoded::Invalid(len + 1),
+            other => other,
+        },
+    }
+}
+
+/// Decode an `CSI` (`ESC [`) sequence.
+fn decode_csi(buf: &[u8]) -> Decoded {
+    // The linux console sends `ESC [ [ A` up to and including `ESC [ [ E` for F1 up to and including F5.
+    if buf[2] == b'[' {
+        return match buf.get(3) {
+            Some(&c @ b'A'..=b'E') => Decoded::Key(KeyEvent::F(1 + c - b'A'), 4),
+            Some(_) => Decoded::Invalid(4),
+            None => Decoded::Incomplete,
+        };
+    }
+
+    // The X10 mouse encoding is not terminated by an final byte, it always has three bytes after `ESC [ M`.
+    if buf[2] == b'M' {
+        return match buf.len() {
+            0..=5 => Decoded::Incomplete,
+            _ => match decode_x10_mouse(buf[3], buf[4], buf[5]) {
+                Some(mouse) => Decoded::Mouse(mouse, 6),
+                None => Decoded::Invalid(6),
+            },
+        };
+    }
+
+    // Parameter and intermediate bytes are followed by the final byte that identifies the sequence.
+    let end = match buf[2..].iter().position(|b| (0x40..=0x7E).contains(b)) {
+        Some(position) => position + 2,
+        None => return Decoded::Incomplete,
+    };
+    let len = end + 1;
+
+    let params = match str::from_utf8(&buf[2..end]) {
+        Ok(params) => params,
+        Err(_) => return Decoded::Invalid(len),
+    };
+
+    if let Some(params) = params.strip_prefix('<') {
+        return match decode_sgr_mouse(params, buf[end]) {
+            Some(mouse) => Decoded::Mouse(mouse, len),
+            None => Decoded::Invalid(len),
+        };
+    }
+
+    // Modifiers like `ESC [ 1 ; 5 A` are send as second parameter, `1` means that no modifier is pressed.
+    let mut params = params.split(';');
+    let first_param = params.next().unwrap_or("");
+    let modified = params.next().is_some_and(|modifiers| modifiers != "1");
+
+    let key = match buf[end] {
+        b'A' => KeyEvent::Up,
+        b'B' => KeyEvent::Down,
+        b'C' => KeyEvent::Right,
+        b'D' => KeyEvent::Left,
+        b'H' => KeyEvent::Home,
+        b'F' => KeyEvent::End,
+        b'Z' => KeyEvent::BackTab,
+        c @ b'P'..=b'S' => KeyEvent::F(1 + c - b'P'),
+        b'~' => match first_param.parse::<u8>() {
+            Ok(1) | Ok(7) => KeyEvent::Home,
+            Ok(2) => KeyEvent::Insert,
+            Ok(3) => KeyEvent::Delete,
+            Ok(4) | Ok(8) => KeyEvent::End,
+            Ok(5) => KeyEvent::PageUp,
+            Ok(6) => KeyEvent::PageDown,
+            Ok(n @ 11..=15) => KeyEvent::F(n - 10),
+            Ok(n @ 17..=21) => KeyEvent::F(n - 11),
+            Ok(n @ 23..=24) => KeyEvent::F(n - 12),
+            _ => return Decoded::Invalid(len),
+        },
+        _ => return Decoded::Invalid(len),
+    };
+
+    // `KeyEvent` can not report the modifiers of these keys, reporting the key without them would be an different key.
+    if modified {
+        return Decoded::Unsupported(len);
+    }
+
+    Decoded::Key(key, len)
+}
+
+/// Decode an `SGR` mouse sequence from its parameters (`b ; x ; y`) and final byte (`M` for press, `m` for release).
+fn decode_sgr_mouse(params: &str, final_byte: u8) -> Option<MouseEvent> {
+    let mut params = params.split(';').map(|param| param.parse::<u16>());
+
+    let (cb, cx, cy) = match (params.next(), params.next(), params.next(), params.next()) {
+        (Some(Ok(cb)), Some(Ok(cx)), Some(Ok(cy)), None) => (cb, cx, cy),
+        _ => return None,
+    };
+
+    // The SGR encoding reports which button is released, so the release does not use the button bits like X10 does.
+    let released = match final_byte {
+        b'M' => false,
+        b'm' => true,
+        _ => return None,
+    };
+
+    mouse_event(cb, cx, cy, released)
+}
+
+/// Decode an `X10` mouse sequence, each byte is the value offset by 32.
+fn decode_x10_mouse(cb: u8, cx: u8, cy: u8) -> Option<MouseEvent> {
+    if cb < 32 || cx < 32 || cy < 32 {
+        return None;
+    }
+
+    mouse_event(u16::from(cb - 32), u16::from(cx - 32), u16::from(cy - 32), false)
+}
+
+/// Create an `MouseEvent` from the button value and the 1-based coordinates the terminal reported.
+///
+/// The lowest two bits of the button value are the button (3 is an release in the `X10` encoding),
+/// 4 is shift, 8 is alt, 16 is ctrl, 32 means the mouse moved and 64 means the wheel is used.
+fn mouse_event(cb: u16, cx: u16, cy: u16, released: bool) -> Option<MouseEvent> {
+    // The terminal starts counting at 1 and crossterm starts counting at 0.
+    if cx == 0 || cy == 0 {
+        return None;
+    }
+
+    let modifiers = KeyModifiers {
+        shift: cb & 4 != 0,
+        alt: cb & 8 != 0,
+        ctrl: cb & 16 != 0,
+    };
+
+    let button = match cb & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+
+    let (kind, button) = if cb & 64 != 0 {
+        match cb & 3 {
+            0 => (MouseEventKind::ScrollUp, None),
+            1 => (MouseEventKind::ScrollDown, None),
+            _ => return None,
+        }
+    } else if cb & 32 != 0 {
+        match button {
+            Some(button) => (MouseEventKind::Drag, Some(button)),
+            None => (MouseEventKind::Move, None),
+        }
+    } else if released || button.is_none() {
+        (MouseEventKind::Release, button)
+    } else {
+        (MouseEventKind::Press, button)
+    };
+
+    Some(MouseEvent {
+        kind,
+        button,
+        x: cx - 1,
+        y: cy - 1,
+        modifiers,
+    })
+}
+
+/// Decode an (multi byte) `UTF-8` character.
+fn decode_char(buf: &[u8]) -> Decoded {
+    let len = match buf[0] {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return Decoded::Invalid(1),
+    };
+
+    if buf.len() < len {
+        return Decoded::Incomplete;
+    }
+
+    match str::from_utf8(&buf[..len]) {
+        Ok(s) => Decoded::Key(KeyEvent::Char(s.chars().next().unwrap()), len),
+        Err(_) => Decoded::Invalid(len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, Decoded};
+    use input::{KeyEvent, MouseButton, MouseEventKind};
+
+    #[test]
+    fn decodes_control_characters() {
+        assert_eq!(decode(b"\r"), Decoded::Key(KeyEvent::Enter, 1));
+        assert_eq!(decode(b"\t"), Decoded::Key(KeyEvent::Tab, 1));
+        assert_eq!(decode(b"\x7F"), Decoded::Key(KeyEvent::Backspace, 1));
+        assert_eq!(decode(b"\x01"), Decoded::Key(KeyEvent::Ctrl('a'), 1));
+        assert_eq!(decode(b"\x1A"), Decoded::Key(KeyEvent::Ctrl('z'), 1));
+        assert_eq!(decode(b"\x1C"), Decoded::Key(KeyEvent::Ctrl('4'), 1));
+    }
+
+    #[test]
+    fn decodes_only_the_first_key() {
+        assert_eq!(decode(b"ab"), Decoded::Key(KeyEvent::Char('a'), 1));
+        assert_eq!(decode(b"\x1B[Ax"), Decoded::Key(KeyEvent::Up, 3));
+    }
+
+    #[test]
+    fn waits_for_the_rest_of_an_split_utf8_character() {
+        let bytes = "\u{754C}".as_bytes();
+
+        assert_eq!(decode(&bytes[..1]), Decoded::Incomplete);
+        assert_eq!(decode(&bytes[..2]), Decoded::Incomplete);
+        assert_eq!(decode(bytes), Decoded::Key(KeyEvent::Char('\u{754C}'), 3));
+        assert_eq!(
+            decode("\u{1F600}".as_bytes()),
+            Decoded::Key(KeyEvent::Char('\u{1F600}'), 4)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        assert_eq!(decode(b"\xFF"), Decoded::Invalid(1));
+        assert_eq!(decode(b"\xC3\x28"), Decoded::Invalid(2));
+    }
+
+    #[test]
+    fn decodes_escape_and_alt() {
+        assert_eq!(decode(b"\x1B"), Decoded::Key(KeyEvent::Esc, 1));
+        assert_eq!(decode(b"\x1B\x1B"), Decoded::Key(KeyEvent::Esc, 1));
+        assert_eq!(decode(b"\x1Bx"), Decoded::Key(KeyEvent::Alt('x'), 2));
+    }
+
+    #[test]
+    fn waits_for_the_final_byte_of_an_split_csi_sequence() {
+        assert_eq!(decode(b"\x1B[1;5"), Decoded::Incomplete);
+        assert_eq!(decode(b"\x1B[1;1A"), Decoded::Key(KeyEvent::Up, 6));
+        assert_eq!(decode(b"\x1B[15"), Decoded::Incomplete);
+        assert_eq!(decode(b"\x1B[15~"), Decoded::Key(KeyEvent::F(5), 5));
+        assert_eq!(decode(b"\x1B["), Decoded::Incomplete);
+        assert_eq!(decode(b"\x1B[["), Decoded::Incomplete);
+        assert_eq!(decode(b"\x1B[[A"), Decoded::Key(KeyEvent::F(1), 4));
+    }
+
+    #[test]
+    fn decodes_csi_and_ss3_keys() {
+        assert_eq!(decode(b"\x1B[3~"), Decoded::Key(KeyEvent::Delete, 4));
+        assert_eq!(decode(b"\x1B[24~"), Decoded::Key(KeyEvent::F(12), 5));
+        assert_eq!(decode(b"\x1B[Z"), Decoded::Key(KeyEvent::BackTab, 3));
+        assert_eq!(decode(b"\x1BOP"), Decoded::Key(KeyEvent::F(1), 3));
+        assert_eq!(decode(b"\x1BOH"), Decoded::Key(KeyEvent::Home, 3));
+    }
+
+    #[test]
+    fn does_not_drop_the_modifiers_of_keys() {
+        assert_eq!(decode(b"\x1B[1;5A"), Decoded::Unsupported(6));
+        assert_eq!(decode(b"\x1B[1;2C"), Decoded::Unsupported(6));
+        assert_eq!(decode(b"\x1B[3;5~"), Decoded::Unsupported(6));
+        assert_eq!(decode(b"\x1B[1;1C"), Decoded::Key(KeyEvent::Right, 6));
+    }
+
+    #[test]
+    fn rejects_unknown_sequences_as_a_whole() {
+        assert_eq!(decode(b"\x1B[99~"), Decoded::Invalid(5));
+        assert_eq!(decode(b"\x1B[1;2q"), Decoded::Invalid(6));
+        assert_eq!(decode(b"\x1BOx"), Decoded::Invalid(3));
+    }
+
+    #[test]
+    fn decodes_sgr_mouse_events() {
+        match decode(b"\x1B[<0;10;5M") {
+            Decoded::Mouse(mouse, 10) => {
+                assert_eq!(mouse.kind, MouseEventKind::Press);
+                assert_eq!(mouse.button, Some(MouseButton::Left));
+                assert_eq!((mouse.x, mouse.y), (9, 4));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        match decode(b"\x1B[<2;1;1m") {
+            Decoded::Mouse(mouse, 9) => {
+                assert_eq!(mouse.kind, MouseEventKind::Release);
+                assert_eq!(mouse.button, Some(MouseButton::Right));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert_eq!(decode(b"\x1B[<0;10"), Decoded::Incomplete);
+        assert_eq!(decode(b"\x1B[<0;0;1M"), Decoded::Invalid(9));
+    }
+
+    #[test]
+    fn decodes_x10_mouse_events() {
+        assert_eq!(decode(b"\x1B[M "), Decoded::Incomplete);
+
+        match decode(b"\x1B[M`!!") {
+            Decoded::Mouse(mouse, 6) => assert_eq!(mouse.kind, MouseEventKind::ScrollUp),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! This is an WINAPI specific implementation for input related action.
+//! The console reports the pressed keys as input records, this module translates those into an `Event`.
+
+use super::{
+    Event, ITerminalInput, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use kernel::windows_kernel::kernel;
+use winapi::shared::minwindef::DWORD;
+use winapi::um::wincon::{
+    FROM_LEFT_1ST_BUTTON_PRESSED, FROM_LEFT_2ND_BUTTON_PRESSED, KEY_EVENT, KEY_EVENT_RECORD,
+    LEFT_ALT_PRESSED, LEFT_CTRL_PRESSED, MOUSE_EVENT, MOUSE_EVENT_RECORD, MOUSE_MOVED,
+    MOUSE_WHEELED, RIGHTMOST_BUTTON_PRESSED, RIGHT_ALT_PRESSED, RIGHT_CTRL_PRESSED,
+    SHIFT_PRESSED,
+};
+use winapi::um::winuser;
+use Construct;
+
+use std::char;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// This struct is an windows implementation for input related actions.
+pub struct WinApiInput {
+    /// Event that is read from the console by `poll()` but not yet returned by `read()`.
+    pending: Option<Event>,
+    /// The mouse buttons that were held down at the previous mouse record,
+    /// the console only reports the button state so this is used to find out which button is pressed or released.
+    mouse_buttons: DWORD,
+}
+
+impl Construct for WinApiInput {
+    fn new() -> Box<WinApiInput> {
+        Box::from(WinApiInput {
+            pending: None,
+            mouse_buttons: 0,
+        })
+    }
+}
+
+impl ITerminalInput for WinApiInput {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        let deadline = Instant::now() + timeout;
+
+        // The console also has input records we do not report, like key releases,
+        // so we need to read the records until we find an event or until the timeout elapsed.
+        while self.pending.is_none() {
+            let now = Instant::now();
+            let left = if deadline > now { deadline - now } else { Duration::from_secs(0) };
+
+            if !kernel::wait_for_console_input(left)? {
+                return Ok(false);
+            }
+
+            self.pending = self.read_console_event()?;
+        }
+
+        Ok(true)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        if let Some(event) = self.pending.take() {
+            return Ok(event);
+        }
+
+        loop {
+            if let Some(event) = self.read_console_event()? {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+impl WinApiInput {
+    /// Read the next input record from the console and translate it into an `Event`,
+    /// `None` is returned when the record is not reported as an event.
+    fn read_console_event(&mut self) -> io::Result<Option<Event>> {
+        let record = kernel::read_console_input()?;
+
+        match record.EventType {
+            KEY_EVENT => {
+                let key_event = unsafe { record.Event.KeyEvent() };
+
+                // Only key presses are reported, key releases are ignored.
+                if key_event.bKeyDown == 0 {
+                    return Ok(None);
+                }
+
+                Ok(translate_key_event(key_event).map(Event::Key))
+            }
+            MOUSE_EVENT => {
+                let mouse_event = unsafe { record.Event.MouseEvent() };
+                Ok(self.translate_mouse_event(mouse_event).map(Event::Mouse))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Translate the given mouse record into an `MouseEvent`, `None` is returned when nothing changed.
+    fn translate_mouse_event(&mut self, mouse_event: &MOUSE_EVENT_RECORD) -> Option<MouseEvent> {
+        let button_state = mouse_event.dwButtonState;
+
+        let (kind, button) = if mouse_event.dwEventFlags & MOUSE_WHEELED != 0 {
+            // The high word of the button state is the signed distance the wheel is scrolled.
+            if (button_state as i32 >> 16) > 0 {
+                (MouseEventKind::ScrollUp, None)
+            } else {
+                (MouseEventKind::ScrollDown, None)
+            }
+        } else {
+            let buttons = button_state & MOUSE_BUTTONS;
+            let changed = buttons ^ self.mouse_buttons;
+            self.mouse_buttons = buttons;
+
+            if changed != 0 {
+                if buttons & changed != 0 {
+                    (MouseEventKind::Press, mouse_button(changed))
+                } else {
+                    (MouseEventKind::Release, mouse_button(changed))
+                }
+            } else if mouse_event.dwEventFlags & MOUSE_MOVED != 0 {
+                match mouse_button(buttons) {
+                    Some(button) => (MouseEventKind::Drag, Some(button)),
+                    None => (MouseEventKind::Move, None),
+                }
+            } else {
+                return None;
+            }
+        };
+
+        let key_state = mouse_event.dwControlKeyState;
+
+        Some(MouseEvent {
+            kind,
+            button,
+            x: mouse_event.dwMousePosition.X as u16,
+            y: mouse_event.dwMousePosition.Y as u16,
+            modifiers: KeyModifiers {
+                shift: key_state & SHIFT_PRESSED != 0,
+                alt: key_state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0,
+                ctrl: key_state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0,
+            },
+        })
+    }
+}
+
+/// The button state bits of the buttons that are reported.
+const MOUSE_BUTTONS: DWORD =
+    FROM_LEFT_1ST_BUTTON_PRESSED | RIGHTMOST_BUTTON_PRESSED | FROM_LEFT_2ND_BUTTON_PRESSED;
+
+/// Get the first mouse button from the given button state.
+fn mouse_button(button_state: DWORD) -> Option<MouseButton> {
+    if button_state & FROM_LEFT_1ST_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Left)
+    } else if button_state & RIGHTMOST_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Right)
+    } else if button_state & FROM_LEFT_2ND_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Middle)
+    } else {
+        None
+    }
+}
+
+/// Translate the given key record into an `KeyEvent`, `None` is returned for keys that are not reported like shift.
+fn translate_key_event(key_event: &KEY_EVENT_RECORD) -> Option<KeyEvent> {
+    let key_state = key_event.dwControlKeyState;
+    let alt = key_state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0;
+    let ctrl = key_state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0;
+    let shift = key_state & SHIFT_PRESSED != 0;
+
+    let key = match key_event.wVirtualKeyCode as i32 {
+        winuser::VK_BACK => KeyEvent::Backspace,
+        winuser::VK_RETURN => KeyEvent::Enter,
+        winuser::VK_LEFT => KeyEvent::Left,
+        winuser::VK_RIGHT => KeyEvent::Right,
+        winuser::VK_UP => KeyEvent::Up,
+        winuser::VK_DOWN => KeyEvent::Down,
+        winuser::VK_HOME => KeyEvent::Home,
+        winuser::VK_END => KeyEvent::End,
+        winuser::VK_PRIOR => KeyEvent::PageUp,
+        winuser::VK_NEXT => KeyEvent::PageDown,
+        winuser::VK_TAB if shift => KeyEvent::BackTab,
+        winuser::VK_TAB => KeyEvent::Tab,
+        winuser::VK_DELETE => KeyEvent::Delete,
+        winuser::VK_INSERT => KeyEvent::Insert,
+        winuser::VK_ESCAPE => KeyEvent::Esc,
+        code @ winuser::VK_F1..=winuser::VK_F12 => KeyEvent::F((code - winuser::VK_F1 + 1) as u8),
+        _ => {
+            let unicode = unsafe { *key_event.uChar.UnicodeChar() } as u32;
+
+            if unicode == 0 {
+                return None;
+            }
+
+            let c = char::from_u32(unicode)?;
+
+            if ctrl && unicode <= 0x1F {
+                match c {
+                    '\0' => KeyEvent::Null,
+                    c @ '\x01'..='\x1A' => KeyEvent::Ctrl((c as u8 - 0x1 + b'a') as char),
+                    '\x1B' => KeyEvent::Esc,
+                    c => KeyEvent::Ctrl((c as u8 - 0x1C + b'4') as char),
+                }
+            } else if alt {
+                KeyEvent::Alt(c)
+            } else {
+                KeyEvent::Char(c)
+            }
+        }
+    };
+
+    Some(key)
+}
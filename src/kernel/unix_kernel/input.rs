@@ -0,0 +1,87 @@
+//! This module contains all `unix` specific input related logic.
+
+use libc::{self, c_int, c_void, pollfd, POLLIN, STDIN_FILENO};
+
+use std::io;
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
+
+/// Read the bytes that are available on stdin into the given buffer.
+///
+/// This call blocks until at least one byte can be read and returns the number of bytes that are read, `0` means the input is closed.
+pub fn read_stdin(buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        let read = unsafe { libc::read(STDIN_FILENO, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+
+        if read >= 0 {
+            return Ok(read as usize);
+        }
+
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+/// Wait until stdin has bytes that can be read or until the given timeout elapsed, `None` waits forever.
+///
+/// Returns whether bytes can be read from stdin.
+pub fn poll_stdin(timeout: Option<Duration>) -> io::Result<bool> {
+    Ok(poll_fds(&[STDIN_FILENO], timeout)?[0])
+}
+
+/// Wait until one of the given file descriptors can be read or until the given timeout elapsed, `None` waits forever.
+///
+/// Returns for each file descriptor whether it can be read.
+pub fn poll_fds(fds: &[RawFd], timeout: Option<Duration>) -> io::Result<Vec<bool>> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    let mut poll_fds: Vec<pollfd> = fds
+        .iter()
+        .map(|&fd| pollfd {
+            fd,
+            events: POLLIN,
+            revents: 0,
+        })
+        .collect();
+
+    loop {
+        let result = unsafe {
+            libc::poll(
+                poll_fds.as_mut_ptr(),
+                poll_fds.len() as libc::nfds_t,
+                poll_timeout(deadline),
+            )
+        };
+
+        // An closed or broken file descriptor can also be read, the read will report what is wrong.
+        if result >= 0 {
+            return Ok(poll_fds.iter().map(|fd| fd.revents != 0).collect());
+        }
+
+        // When the poll is interrupted by a signal we continue to wait for the time that is left.
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+/// Get the timeout in milliseconds for `poll(2)` from the given deadline, `-1` means an infinite timeout.
+fn poll_timeout(deadline: Option<Instant>) -> c_int {
+    match deadline {
+        Some(deadline) => {
+            let now = Instant::now();
+
+            if deadline <= now {
+                0
+            } else {
+                // round up so that we do not wake up just before the deadline.
+                let millis = (deadline - now).as_nanos().div_ceil(1_000_000);
+                millis.min(c_int::MAX as u128) as c_int
+            }
+        }
+        None => -1,
+    }
+}
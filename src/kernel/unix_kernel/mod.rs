@@ -1,3 +1,5 @@
 //! This module contains all the specific `unix` code.
 
+pub mod input;
+pub mod signal;
 pub mod terminal;
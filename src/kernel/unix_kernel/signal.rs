@@ -0,0 +1,386 @@
+//! This module contains all `unix` specific signal handling.
+//!
+//! Almost nothing is allowed inside an signal handler, it can not even lock an mutex.
+//! That is why the signal handler only writes the signal number into an pipe (the self-pipe trick).
+//! An background thread reads the signal numbers from that pipe and calls the registered handlers, where everything is allowed.
+
+use libc::{self, c_int, c_void};
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::{mem, thread};
+
+/// An function that is called on the signal thread when the signal it is registered for is received.
+type SignalHandler = Arc<dyn Fn() + Send + Sync>;
+
+/// The registered handlers with the signal they are registered for and an unique id.
+struct Registry {
+    handlers: Vec<(c_int, usize, SignalHandler)>,
+    /// The signals for which the signal handler is installed, with the action they had before.
+    installed: Vec<(c_int, libc::sigaction)>,
+    next_id: usize,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    handlers: Vec::new(),
+    installed: Vec::new(),
+    next_id: 0,
+});
+
+/// The write end of the pipe the signal handler writes to, `-1` when the pipe is not created yet.
+static PIPE_WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+
+static START_SIGNAL_THREAD: Once = Once::new();
+
+/// Register an handler that is called when the given signal is received.
+///
+/// The handler is not called inside the signal handler but on an background thread, so it may do anything.
+/// Returns an id that can be used to unregister the handler.
+pub fn register<F>(signal: c_int, handler: F) -> io::Result<usize>
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    start_signal_thread()?;
+
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+
+    if !registry
+        .installed
+        .iter()
+        .any(|&(installed, _)| installed == signal)
+    {
+        let previous = install_handler(signal)?;
+        registry.installed.push((signal, previous));
+    }
+
+    let id = registry.next_id;
+    registry.next_id += 1;
+    registry.handlers.push((signal, id, Arc::new(handler)));
+
+    Ok(id)
+}
+
+/// Unregister the handler with the given id.
+///
+/// When it was the last handler for its signal the action the signal had before the first handler was registered is restored.
+pub fn unregister(id: usize) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+
+    let signal = match registry
+        .handlers
+        .iter()
+        .position(|&(_, handler_id, _)| handler_id == id)
+    {
+        Some(index) => registry.handlers.remove(index).0,
+        None => return,
+    };
+
+    if registry
+        .handlers
+        .iter()
+        .all(|&(handler_signal, _, _)| handler_signal != signal)
+    {
+        if let Some(index) = registry
+            .installed
+            .iter()
+            .position(|&(installed, _)| installed == signal)
+        {
+            let (_, previous) = registry.installed.remove(index);
+            unsafe {
+                libc::sigaction(signal, &previous, ::std::ptr::null_mut());
+            }
+        }
+    }
+}
+
+/// Check whether the given signal is ignored, like `SIGHUP` in an process that is started by `nohup`.
+///
+/// When the signal handler is installed for the signal, the action the signal had before is checked.
+pub fn is_ignored(signal: c_int) -> bool {
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+
+    let action = match registry
+        .installed
+        .iter()
+        .find(|&&(installed, _)| installed == signal)
+    {
+        Some(&(_, previous)) => previous,
+        None => unsafe {
+            let mut current: libc::sigaction = mem::zeroed();
+            if libc::sigaction(signal, ::std::ptr::null(), &mut current) == -1 {
+                return false;
+            }
+            current
+        },
+    };
+
+    action.sa_sigaction == libc::SIG_IGN
+}
+
+/// Raise the given signal again with the action it had before the first handler was registered,
+/// so the process ends the way it would have without the handlers.
+///
+/// This is meant for handlers of signals that terminate the process, after they cleaned up.
+pub fn reraise(signal: c_int) {
+    let previous = {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        registry
+            .installed
+            .iter()
+            .position(|&(installed, _)| installed == signal)
+            .map(|index| registry.installed.remove(index).1)
+    };
+
+    unsafe {
+        match previous {
+            Some(previous) => {
+                libc::sigaction(signal, &previous, ::std::ptr::null_mut());
+            }
+            None => {
+                set_default_action(signal);
+            }
+        }
+        libc::raise(signal);
+    }
+}
+
+/// Raise the given signal with its default action and install the current action again afterwards.
+///
+/// This is meant for handlers of signals that stop the process like `SIGTSTP`, it returns when the process is continued.
+pub fn raise_default(signal: c_int) {
+    unsafe {
+        let current = set_default_action(signal);
+        libc::raise(signal);
+        libc::sigaction(signal, &current, ::std::ptr::null_mut());
+    }
+}
+
+/// Set the action of the given signal to its default action, returns the action it had.
+unsafe fn set_default_action(signal: c_int) -> libc::sigaction {
+    let mut current: libc::sigaction = mem::zeroed();
+    let mut action: libc::sigaction = mem::zeroed();
+    action.sa_sigaction = libc::SIG_DFL;
+    libc::sigemptyset(&mut action.sa_mask);
+    libc::sigaction(signal, &action, &mut current);
+    current
+}
+
+/// An pipe that receives an byte each time the signal it is created for is received.
+///
+/// This can be used to wait for signals with `poll(2)` together with other file descriptors.
+pub struct SignalPipe {
+    read: File,
+    id: usize,
+}
+
+impl SignalPipe {
+    /// Create an pipe that receives an byte each time the given signal is received.
+    pub fn new(signal: c_int) -> io::Result<SignalPipe> {
+        let (read, write) = create_pipe()?;
+
+        let id = register(signal, move || {
+            // When the pipe is full the reader has not handled the previous signals yet, so this one can be dropped.
+            let _ = (&write).write(&[1]);
+        })?;
+
+        Ok(SignalPipe { read, id })
+    }
+
+    /// Get the file descriptor that becomes readable when the signal is received.
+    pub fn fd(&self) -> RawFd {
+        self.read.as_raw_fd()
+    }
+
+    /// Read all bytes from the pipe, returns whether the signal was received since the last call.
+    pub fn drain(&mut self) -> bool {
+        let mut received = false;
+        let mut buf = [0u8; 32];
+
+        while let Ok(read) = self.read.read(&mut buf) {
+            if read == 0 {
+                break;
+            }
+            received = true;
+        }
+
+        received
+    }
+}
+
+impl Drop for SignalPipe {
+    fn drop(&mut self) {
+        unregister(self.id);
+    }
+}
+
+/// Create an pipe with an non blocking read and write end, the file descriptors are not inherited by child processes.
+fn create_pipe() -> io::Result<(File, File)> {
+    let mut fds = [0 as c_int; 2];
+
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let (read, write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+
+    for fd in &fds {
+        unsafe {
+            let flags = libc::fcntl(*fd, libc::F_GETFL);
+            if flags == -1
+                || libc::fcntl(*fd, libc::F_SETFL, flags | libc::O_NONBLOCK) == -1
+                || libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) == -1
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+    }
+
+    Ok((read, write))
+}
+
+/// Create the self-pipe and start the thread that calls the registered handlers, this only happens once.
+fn start_signal_thread() -> io::Result<()> {
+    let mut result = Ok(());
+
+    START_SIGNAL_THREAD.call_once(|| {
+        result = create_pipe().and_then(|(read, write)| -> io::Result<()> {
+            // the read end is only used by the signal thread, so it can block.
+            unsafe {
+                let flags = libc::fcntl(read.as_raw_fd(), libc::F_GETFL);
+                libc::fcntl(read.as_raw_fd(), libc::F_SETFL, flags & !libc::O_NONBLOCK);
+            }
+
+            thread::Builder::new()
+                .name("crossterm-signals".to_string())
+                .spawn(move || run_signal_thread(read))?;
+
+            // The write end is never closed, the signal handler can use it for the rest of the process.
+            PIPE_WRITE_FD.store(write.as_raw_fd(), Ordering::SeqCst);
+            mem::forget(write);
+            Ok(())
+        });
+    });
+
+    // The thread is only started once, so when that failed the error is only known by the first caller.
+    if result.is_ok() && PIPE_WRITE_FD.load(Ordering::SeqCst) == -1 {
+        return Err(io::Error::other("the signal thread could not be started"));
+    }
+
+    result
+}
+
+/// Read the signal numbers from the self-pipe and call the handlers that are registered for them.
+fn run_signal_thread(mut read: File) {
+    let mut buf = [0u8; 32];
+
+    loop {
+        let count = match read.read(&mut buf) {
+            Ok(0) => return,
+            Ok(count) => count,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+
+        for &signal in &buf[..count] {
+            // The handlers are called without holding the lock, so that they can register and unregister handlers.
+            let handlers: Vec<SignalHandler> = {
+                let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+                registry
+                    .handlers
+                    .iter()
+                    .filter(|&&(handler_signal, _, _)| handler_signal == c_int::from(signal))
+                    .map(|(_, _, handler)| handler.clone())
+                    .collect()
+            };
+
+            for handler in handlers {
+                handler();
+            }
+        }
+    }
+}
+
+/// Install the signal handler that forwards the given signal to the self-pipe, returns the action the signal had before.
+fn install_handler(signal: c_int) -> io::Result<libc::sigaction> {
+    unsafe {
+        let mut previous: libc::sigaction = mem::zeroed();
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = forward_signal as extern "C" fn(c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+
+        if libc::sigaction(signal, &action, &mut previous) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(previous)
+    }
+}
+
+/// The signal handler, it only writes the signal number to the self-pipe.
+///
+/// `errno` is restored afterwards, the code the signal interrupted may still have to read it.
+extern "C" fn forward_signal(signal: c_int) {
+    let fd = PIPE_WRITE_FD.load(Ordering::SeqCst);
+
+    if fd != -1 {
+        let byte = signal as u8;
+        unsafe {
+            let errno = errno_location();
+            let saved = errno.map(|errno| *errno);
+
+            libc::write(fd, &byte as *const u8 as *const c_void, 1);
+
+            if let (Some(errno), Some(saved)) = (errno, saved) {
+                *errno = saved;
+            }
+        }
+    }
+}
+
+/// Get the location of `errno` of the current thread, `None` on the platforms where it is not known.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "hurd"
+))]
+unsafe fn errno_location() -> Option<*mut c_int> {
+    Some(libc::__errno_location())
+}
+
+#[cfg(any(target_os = "android", target_os = "openbsd", target_os = "netbsd"))]
+unsafe fn errno_location() -> Option<*mut c_int> {
+    Some(libc::__errno())
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+unsafe fn errno_location() -> Option<*mut c_int> {
+    Some(libc::__error())
+}
+
+#[cfg(any(target_os = "solaris", target_os = "illumos"))]
+unsafe fn errno_location() -> Option<*mut c_int> {
+    Some(libc::___errno())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "hurd",
+    target_os = "android",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "solaris",
+    target_os = "illumos"
+)))]
+unsafe fn errno_location() -> Option<*mut c_int> {
+    None
+}
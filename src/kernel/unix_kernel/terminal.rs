@@ -1,13 +1,15 @@
 //! This module contains all `unix` specific terminal related logic.
 
-pub use self::libc::termios;
-use self::libc::{c_int, c_ushort, ioctl, STDOUT_FILENO, TIOCGWINSZ};
+use self::libc::{c_ushort, ioctl, STDERR_FILENO, STDIN_FILENO, STDOUT_FILENO, TIOCGWINSZ};
+use super::input;
+use state;
 use state::commands::{IContextCommand, NoncanonicalModeCommand};
-use termios::Termios;
-use {libc, Context};
+use termios::{self, Termios};
+use {libc, Error, ErrorKind};
 
-use std::io::Error;
-use std::{io, mem};
+use std::io;
+use std::os::unix::io::RawFd;
+use std::time::Duration;
 
 /// A representation of the size of the current terminal.
 #[repr(C)]
@@ -21,8 +23,38 @@ pub struct UnixSize {
     y: c_ushort,
 }
 
+/// Check whether stdout is an terminal.
+pub fn stdout_is_tty() -> bool {
+    unsafe { libc::isatty(STDOUT_FILENO) == 1 }
+}
+
 /// Get the current terminal size.
-pub fn terminal_size() -> (u16, u16) {
+///
+/// The size is read from stdout, when stdout is redirected it is read from stderr or stdin instead
+/// so that the size is also known when the output is written to stderr while stdout is piped.
+/// When a stream is a terminal but its size can't be read the error names that stream.
+pub fn terminal_size() -> io::Result<(u16, u16)> {
+    let streams = [
+        (STDOUT_FILENO, "stdout"),
+        (STDERR_FILENO, "stderr"),
+        (STDIN_FILENO, "stdin"),
+    ];
+
+    for &(fd, stream) in &streams {
+        match size_of_fd(fd, stream) {
+            Err(ref e) if Error::kind_of(e) == Some(ErrorKind::NotATerminal) => {}
+            size => return size,
+        }
+    }
+
+    Err(Error::new(
+        ErrorKind::NotATerminal,
+        "none of stdout, stderr and stdin is a terminal",
+    ).into())
+}
+
+/// Get the size of the terminal the given file descriptor, with the given name, refers to.
+fn size_of_fd(fd: RawFd, stream: &str) -> io::Result<(u16, u16)> {
     // http://rosettacode.org/wiki/Terminal_control/Dimensions#Library:_BSD_libc
     let us = UnixSize {
         rows: 0,
@@ -30,112 +62,142 @@ pub fn terminal_size() -> (u16, u16) {
         x: 0,
         y: 0,
     };
-    let r = unsafe { ioctl(STDOUT_FILENO, TIOCGWINSZ, &us) };
-    if r == 0 {
-        // because crossterm works starts counting at 0 and unix terminal starts at cell 1 you have subtract one to get 0-based results.
-        (us.cols - 1, us.rows - 1)
-    } else {
-        (0, 0)
+    let r = unsafe { ioctl(fd, TIOCGWINSZ, &us) };
+
+    if r != 0 {
+        return Err(tty_error(io::Error::last_os_error(), stream));
+    }
+
+    // Some pseudo terminals, like the ones of an serial console, do not know their size.
+    if us.cols == 0 || us.rows == 0 {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!("the terminal of {} did not report its size", stream),
+        ).into());
     }
+
+    // because crossterm works starts counting at 0 and unix terminal starts at cell 1 you have subtract one to get 0-based results.
+    Ok((us.cols - 1, us.rows - 1))
 }
 
+/// The time in milliseconds to wait for the terminal to report the cursor position.
+const CURSOR_POSITION_TIMEOUT: u64 = 2000;
+
 /// Get the current cursor position.
-pub fn pos() -> (u16, u16) {
-    use std::io::Error;
-    use std::io::{Read, Write};
-
-    let mut context = Context::new();
-    {
-        let mut (command, _) = NoncanonicalModeCommand::new(&mut context);
-        command.execute();
-
-        // This code is original written by term_cursor credits to them.
-        let mut stdout = io::stdout();
-
-        // Write command
-        stdout.write(b"\x1B[6n");
-        stdout.flush();
-
-        // Read back result
-        let mut buf = [0u8; 2];
-        // Expect `ESC[`
-        io::stdin().read_exact(&mut buf);
-        if buf[0] != 0x1B || buf[1] as char != '[' {
-            return (0, 0);
-        }
+///
+/// The noncanonical mode the position is read in is registered in the shared context while the position is read,
+/// so it is also restored when the program is terminated in the meantime.
+pub fn pos() -> io::Result<(u16, u16)> {
+    let mut context = state::shared_context().clone();
+
+    let (mut command, key) = NoncanonicalModeCommand::new(&mut context);
+    if let Err(e) = command.execute() {
+        let _ = context.undo_state(key);
+        return Err(e);
+    }
 
-        // Read rows and cols through a ad-hoc integer parsing function
-        let read_num = || -> (i32, char) {
-            let mut num = 0;
-            let mut c;
-
-            loop {
-                let mut buf = [0u8; 1];
-                io::stdin().read_exact(&mut buf);
-                c = buf[0] as char;
-                if let Some(d) = c.to_digit(10) {
-                    num = if num == 0 { 0 } else { num * 10 };
-                    num += d as i32;
-                } else {
-                    break;
-                }
-            }
+    let position = read_cursor_position();
+    // the terminal should always leave noncanonical mode, even when reading the position failed.
+    let restored = context.undo_state(key);
 
-            (num, c)
-        };
+    let position = position?;
+    restored?;
+    Ok(position)
+}
 
-        // Read rows and expect `;`
-        let (rows, c) = read_num();
+/// Request the cursor position from the terminal and read the response, the terminal should be in noncanonical mode.
+fn read_cursor_position() -> io::Result<(u16, u16)> {
+    use std::io::Write;
 
-        if c != ';' {
-            return (0, 0);
-        }
+    // This code is original written by term_cursor credits to them.
+    let mut stdout = io::stdout();
 
-        // Read cols
-        let (cols, c) = read_num();
+    // Write command
+    stdout.write_all(b"\x1B[6n")?;
+    stdout.flush()?;
 
-        // Expect `R`
-        if c == 'R' {
-            (cols as u16, rows as u16)
+    // Read back result, the terminal may not respond at all so we do not wait forever for an byte.
+    let read_byte = || -> io::Result<u8> {
+        let mut buf = [0u8; 1];
+
+        if input::poll_stdin(Some(Duration::from_millis(CURSOR_POSITION_TIMEOUT)))?
+            && input::read_stdin(&mut buf)? == 1
+        {
+            Ok(buf[0])
         } else {
-            (0, 0);
+            Err(no_cursor_position())
+        }
+    };
+
+    // Expect `ESC[`
+    if read_byte()? != 0x1B || read_byte()? != b'[' {
+        return Err(no_cursor_position());
+    }
+
+    // Read rows and cols through a ad-hoc integer parsing function
+    let read_num = || -> io::Result<(i32, char)> {
+        let mut num = 0;
+        let mut c;
+
+        loop {
+            c = read_byte()? as char;
+            if let Some(d) = c.to_digit(10) {
+                num = if num == 0 { 0 } else { num * 10 };
+                num += d as i32;
+            } else {
+                break;
+            }
         }
+
+        Ok((num, c))
+    };
+
+    // Read rows and expect `;`
+    let (rows, c) = read_num()?;
+
+    if c != ';' {
+        return Err(no_cursor_position());
+    }
+
+    // Read cols
+    let (cols, c) = read_num()?;
+
+    // Expect `R`
+    if c == 'R' {
+        Ok((cols as u16, rows as u16))
+    } else {
+        Err(no_cursor_position())
     }
 }
 
+/// The error that is returned when the terminal did not report the cursor position.
+fn no_cursor_position() -> io::Error {
+    Error::new(
+        ErrorKind::CursorPositionUnavailable,
+        "the terminal did not report the cursor position",
+    ).into()
+}
+
 /// Set the terminal mode to the given mode.
 pub fn set_terminal_mode(termios: &Termios) -> io::Result<()> {
-    extern "C" {
-        pub fn tcsetattr(fd: c_int, opt: c_int, termptr: *const Termios) -> c_int;
-    }
-    is_true(unsafe { tcsetattr(0, 0, termios) }).and(Ok(()))
+    termios::tcsetattr(STDIN_FILENO, termios::TCSANOW, termios).map_err(|e| tty_error(e, "stdin"))
 }
 
 /// Transform the given mode into an raw mode (non-canonical) mode.
 pub fn make_raw(termios: &mut Termios) {
-    extern "C" {
-        pub fn cfmakeraw(termptr: *mut Termios);
-    }
-    unsafe { cfmakeraw(termios) }
+    termios::cfmakeraw(termios)
 }
 
 /// Get the current terminal mode.
 pub fn get_terminal_mode() -> io::Result<Termios> {
-    extern "C" {
-        pub fn tcgetattr(fd: c_int, termptr: *mut Termios) -> c_int;
-    }
-    unsafe {
-        let mut termios = mem::zeroed();
-        is_true(tcgetattr(0, &mut termios))
-            .map(|_| termios)
-    }
+    Termios::from_fd(STDIN_FILENO).map_err(|e| tty_error(e, "stdin"))
 }
 
-/// Is the return value true?
-fn is_true(value: i32) -> Result<(), Error> {
-    match value {
-        -1 => Err(io::Error::last_os_error()),
-        0 => Ok(()),
-        _ => Err(io::Error::last_os_error()),
+/// Report the `ENOTTY` error of the os as an `ErrorKind::NotATerminal` error, other errors are returned as is.
+fn tty_error(error: io::Error, stream: &str) -> io::Error {
+    if error.raw_os_error() == Some(libc::ENOTTY) {
+        Error::new(ErrorKind::NotATerminal, format!("{} is not a terminal", stream)).into()
+    } else {
+        error
     }
 }
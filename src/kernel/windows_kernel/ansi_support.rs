@@ -1,7 +1,6 @@
 //! This module handles the enabling `ANSI escape codes` for windows terminals.
 
 use state::commands::ICommand;
-use Context;
 
 static mut HAS_BEEN_TRYED_TO_ENABLE: bool = false;
 static mut IS_ANSI_ON_WINDOWS_ENABLED: Option<bool> = None;
@@ -11,7 +10,7 @@ static mut DOES_WINDOWS_SUPPORT_ANSI: Option<bool> = None;
 pub fn try_enable_ansi_support() -> bool {
     use state::commands::win_commands::EnableAnsiCommand;
     let mut command = EnableAnsiCommand::new();
-    let success = command.execute();
+    let success = command.execute().is_ok();
 
     set_is_windows_ansi_supportable(success);
     set_ansi_enabled(success);
@@ -1,6 +1,7 @@
 //! This module is the core of all the `WINAPI` actions. All unsafe `WINAPI` function call are done here.
 
-use winapi::shared::minwindef::TRUE;
+use winapi::shared::minwindef::{DWORD, TRUE};
+use winapi::shared::winerror::ERROR_INVALID_HANDLE;
 use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
 use winapi::um::handleapi::INVALID_HANDLE_VALUE;
 use winapi::um::processenv::GetStdHandle;
@@ -8,48 +9,53 @@ use winapi::um::winbase::{STD_INPUT_HANDLE, STD_OUTPUT_HANDLE};
 use winapi::um::wincon;
 use winapi::um::wincon::{
     CreateConsoleScreenBuffer, FillConsoleOutputAttribute, FillConsoleOutputCharacterA,
-    GetConsoleScreenBufferInfo, GetLargestConsoleWindowSize, SetConsoleActiveScreenBuffer,
-    SetConsoleCursorPosition, SetConsoleScreenBufferSize, SetConsoleTextAttribute,
-    SetConsoleWindowInfo, CHAR_INFO, CONSOLE_SCREEN_BUFFER_INFO, COORD, PSMALL_RECT, SMALL_RECT,
+    GetConsoleCursorInfo, GetConsoleScreenBufferInfo, GetLargestConsoleWindowSize,
+    SetConsoleActiveScreenBuffer, SetConsoleCursorInfo, SetConsoleCursorPosition, SetConsoleScreenBufferSize, SetConsoleTextAttribute,
+    SetConsoleWindowInfo, CHAR_INFO, CONSOLE_CURSOR_INFO, CONSOLE_SCREEN_BUFFER_INFO, COORD, INPUT_RECORD, PSMALL_RECT,
+    SMALL_RECT,
 };
 use winapi::um::winnt::HANDLE;
 
 use super::Empty;
+use std::time::Duration;
+use std::{io, mem};
+use {Error, ErrorKind};
+
 static mut CONSOLE_OUTPUT_HANDLE: Option<HANDLE> = None;
 static mut CONSOLE_INPUT_HANDLE: Option<HANDLE> = None;
 
 /// Get the std_output_handle of the console
-pub fn get_output_handle() -> HANDLE {
+pub fn get_output_handle() -> io::Result<HANDLE> {
     unsafe {
         if let Some(handle) = CONSOLE_OUTPUT_HANDLE {
-            handle
+            Ok(handle)
         } else {
             let handle = GetStdHandle(STD_OUTPUT_HANDLE);
 
             if !is_valid_handle(&handle) {
-                panic!("Cannot get output handle")
+                return Err(io::Error::last_os_error());
             }
 
             CONSOLE_OUTPUT_HANDLE = Some(handle);
-            handle
+            Ok(handle)
         }
     }
 }
 
 /// Get the std_input_handle of the console
-pub fn get_input_handle() -> HANDLE {
+pub fn get_input_handle() -> io::Result<HANDLE> {
     unsafe {
         if let Some(handle) = CONSOLE_INPUT_HANDLE {
-            handle
+            Ok(handle)
         } else {
             let handle = GetStdHandle(STD_INPUT_HANDLE);
 
             if !is_valid_handle(&handle) {
-                panic!("Cannot get input handle")
+                return Err(io::Error::last_os_error());
             }
 
             CONSOLE_INPUT_HANDLE = Some(handle);
-            handle
+            Ok(handle)
         }
     }
 }
@@ -60,128 +66,115 @@ pub fn is_valid_handle(handle: &HANDLE) -> bool {
     *handle != INVALID_HANDLE_VALUE
 }
 
-pub fn get_console_screen_buffer_info() -> CONSOLE_SCREEN_BUFFER_INFO {
-    let output_handle = get_output_handle();
+pub fn get_console_screen_buffer_info() -> io::Result<CONSOLE_SCREEN_BUFFER_INFO> {
+    let output_handle = get_output_handle()?;
     let mut csbi = CONSOLE_SCREEN_BUFFER_INFO::empty();
     unsafe {
-        let success = GetConsoleScreenBufferInfo(output_handle, &mut csbi);
-        if success == 0 {
-            panic!("Cannot get console screen buffer info");
-        }
-        csbi
+        result(GetConsoleScreenBufferInfo(output_handle, &mut csbi))?;
     }
+    Ok(csbi)
 }
 
-pub fn get_largest_console_window_size() -> COORD {
-    let output_handle = get_output_handle();
-    unsafe { GetLargestConsoleWindowSize(output_handle) }
+pub fn get_largest_console_window_size() -> io::Result<COORD> {
+    let output_handle = get_output_handle()?;
+    unsafe { Ok(GetLargestConsoleWindowSize(output_handle)) }
 }
 
-pub fn get_original_console_color() -> u16 {
-    get_console_screen_buffer_info().wAttributes as u16
+pub fn get_original_console_color() -> io::Result<u16> {
+    Ok(get_console_screen_buffer_info()?.wAttributes as u16)
 }
 
-pub fn set_console_mode(handle: &HANDLE, console_mode: u32) -> bool {
-    unsafe {
-        let success = SetConsoleMode(*handle, console_mode);
-        is_true(success)
-    }
+pub fn set_console_mode(handle: &HANDLE, console_mode: DWORD) -> io::Result<()> {
+    unsafe { result(SetConsoleMode(*handle, console_mode)) }
 }
 
-pub fn get_console_mode(handle: &HANDLE, current_mode: &mut u32) -> bool {
+pub fn get_console_mode(handle: &HANDLE) -> io::Result<DWORD> {
+    let mut console_mode: DWORD = 0;
     unsafe {
-        let success = GetConsoleMode(*handle, &mut *current_mode);
-        is_true(success)
+        result(GetConsoleMode(*handle, &mut console_mode))?;
     }
+    Ok(console_mode)
 }
 
-pub fn set_console_cursor_position(x: i16, y: i16) {
-    if x < 0 {
-        panic!("X: {}, Argument Out of Range Exception", x);
-    }
-
-    if y < 0 {
-        panic!("Y: {}, Argument Out of Range Exception", y);
+pub fn set_console_cursor_position(x: i16, y: i16) -> io::Result<()> {
+    if x < 0 || y < 0 {
+        return Err(Error::new(
+            ErrorKind::OutOfRange,
+            format!("the cursor position ({}, {}) is out of range", x, y),
+        ).into());
     }
 
-    let output_handle = get_output_handle();
+    let output_handle = get_output_handle()?;
     let position = COORD { X: x, Y: y };
 
-    unsafe {
-        let success = SetConsoleCursorPosition(output_handle, position);
-        if success == 0 {
-            panic!("Argument out of range.");
-        }
-    }
+    unsafe { result(SetConsoleCursorPosition(output_handle, position)) }
 }
 
-pub fn set_console_text_attribute(value: u16) {
-    let output_handle = get_output_handle();
+/// Show or hide the cursor, the size of the cursor is kept.
+pub fn set_console_cursor_visible(visible: bool) -> io::Result<()> {
+    let output_handle = get_output_handle()?;
+
     unsafe {
-        SetConsoleTextAttribute(output_handle, value);
+        let mut cursor_info: CONSOLE_CURSOR_INFO = mem::zeroed();
+        result(GetConsoleCursorInfo(output_handle, &mut cursor_info))?;
+
+        cursor_info.bVisible = if visible { TRUE } else { 0 };
+        result(SetConsoleCursorInfo(output_handle, &cursor_info))
     }
 }
 
-pub fn set_console_info(absolute: bool, rect: &SMALL_RECT) -> bool {
-    let output_handle = get_output_handle();
-    let absolute = match absolute {
-        true => 1,
-        false => 0,
-    };
-    unsafe {
-        let success = SetConsoleWindowInfo(output_handle, absolute, rect);
-        is_true(success)
-    }
+pub fn set_console_text_attribute(value: u16) -> io::Result<()> {
+    let output_handle = get_output_handle()?;
+    unsafe { result(SetConsoleTextAttribute(output_handle, value)) }
 }
 
-pub fn set_console_screen_buffer_size(size: COORD) -> bool {
-    let output_handle = get_output_handle();
-    unsafe {
-        let success = SetConsoleScreenBufferSize(output_handle, size);
-        is_true(success)
-    }
+pub fn set_console_info(absolute: bool, rect: &SMALL_RECT) -> io::Result<()> {
+    let output_handle = get_output_handle()?;
+    let absolute = if absolute { 1 } else { 0 };
+    unsafe { result(SetConsoleWindowInfo(output_handle, absolute, rect)) }
+}
+
+pub fn set_console_screen_buffer_size(size: COORD) -> io::Result<()> {
+    let output_handle = get_output_handle()?;
+    unsafe { result(SetConsoleScreenBufferSize(output_handle, size)) }
 }
 
-pub fn fill_console_output_character(
-    cells_written: &mut u32,
-    start_location: COORD,
-    cells_to_write: u32,
-) -> bool {
-    let output_handle = get_output_handle();
+/// Fill the given amount of cells from the start location with blanks, returns the number of cells written.
+pub fn fill_console_output_character(start_location: COORD, cells_to_write: u32) -> io::Result<u32> {
+    let output_handle = get_output_handle()?;
+    let mut cells_written = 0;
     unsafe {
         // fill the cells in console with blanks
-        let success = FillConsoleOutputCharacterA(
+        result(FillConsoleOutputCharacterA(
             output_handle,
             ' ' as i8,
             cells_to_write,
             start_location,
-            cells_written,
-        );
-        is_true(success)
+            &mut cells_written,
+        ))?;
     }
+    Ok(cells_written)
 }
 
-pub fn fill_console_output_attribute(
-    cells_written: &mut u32,
-    start_location: COORD,
-    cells_to_write: u32,
-) -> bool {
+/// Reset the attributes of the given amount of cells from the start location, returns the number of cells written.
+pub fn fill_console_output_attribute(start_location: COORD, cells_to_write: u32) -> io::Result<u32> {
     // Get the position of the current console window
-    let csbi = get_console_screen_buffer_info();
-    let output_handle = get_output_handle();
+    let csbi = get_console_screen_buffer_info()?;
+    let output_handle = get_output_handle()?;
+    let mut cells_written = 0;
     unsafe {
-        let success = FillConsoleOutputAttribute(
+        result(FillConsoleOutputAttribute(
             output_handle,
             csbi.wAttributes,
             cells_to_write,
             start_location,
-            cells_written,
-        );
-        is_true(success)
+            &mut cells_written,
+        ))?;
     }
+    Ok(cells_written)
 }
 
-pub fn create_console_screen_buffer() -> HANDLE {
+pub fn create_console_screen_buffer() -> io::Result<HANDLE> {
     use std::mem::size_of;
     use winapi::shared::ntdef::NULL;
     use winapi::um::minwinbase::SECURITY_ATTRIBUTES;
@@ -189,7 +182,7 @@ pub fn create_console_screen_buffer() -> HANDLE {
     use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE, GENERIC_READ, GENERIC_WRITE};
 
     unsafe {
-        let mut security_attr = SECURITY_ATTRIBUTES {
+        let security_attr = SECURITY_ATTRIBUTES {
             nLength: size_of::<SECURITY_ATTRIBUTES>() as u32,
             lpSecurityDescriptor: NULL,
             bInheritHandle: TRUE,
@@ -198,21 +191,21 @@ pub fn create_console_screen_buffer() -> HANDLE {
         let new_screen_buffer = CreateConsoleScreenBuffer(
             GENERIC_READ | GENERIC_WRITE,       // read/write access
             FILE_SHARE_READ | FILE_SHARE_WRITE, // shared
-            &mut security_attr,                 // default security attributes
+            &security_attr,                     // default security attributes
             CONSOLE_TEXTMODE_BUFFER,            // must be TEXTMODE
             NULL,
         );
 
-        new_screen_buffer
+        if !is_valid_handle(&new_screen_buffer) {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(new_screen_buffer)
     }
 }
 
-pub fn set_active_screen_buffer(new_buffer: HANDLE) {
-    unsafe {
-        if !is_true(SetConsoleActiveScreenBuffer(new_buffer)) {
-            panic!("Cannot set active screen buffer");
-        }
-    }
+pub fn set_active_screen_buffer(new_buffer: HANDLE) -> io::Result<()> {
+    unsafe { result(SetConsoleActiveScreenBuffer(new_buffer)) }
 }
 
 pub fn read_console_output(
@@ -221,21 +214,17 @@ pub fn read_console_output(
     buffer_size: COORD,
     buffer_coord: COORD,
     source_buffer: PSMALL_RECT,
-) {
+) -> io::Result<()> {
     use self::wincon::ReadConsoleOutputA;
 
     unsafe {
-        if !is_true(
-            ReadConsoleOutputA(
-                *read_buffer,             // screen buffer to read from
-                copy_buffer.as_mut_ptr(), // buffer to copy into
-                buffer_size,              // col-row size of chiBuffer
-                buffer_coord,             // top left dest. cell in chiBuffer
-                source_buffer,
-            ), // screen buffer source rectangle
-        ) {
-            panic!("Cannot read console output");
-        }
+        result(ReadConsoleOutputA(
+            *read_buffer,             // screen buffer to read from
+            copy_buffer.as_mut_ptr(), // buffer to copy into
+            buffer_size,              // col-row size of chiBuffer
+            buffer_coord,             // top left dest. cell in chiBuffer
+            source_buffer,            // screen buffer source rectangle
+        ))
     }
 }
 
@@ -245,26 +234,73 @@ pub fn write_console_output(
     buffer_size: COORD,
     buffer_coord: COORD,
     source_buffer: PSMALL_RECT,
-) {
+) -> io::Result<()> {
     use self::wincon::WriteConsoleOutputA;
 
     unsafe {
-        if !is_true(
-            WriteConsoleOutputA(
-                *write_buffer,            // screen buffer to write to
-                copy_buffer.as_mut_ptr(), // buffer to copy into
-                buffer_size,              // col-row size of chiBuffer
-                buffer_coord,             // top left dest. cell in chiBuffer
-                source_buffer,
-            ), // screen buffer source rectangle
-        ) {
-            panic!("Cannot write to console output");
-        }
+        result(WriteConsoleOutputA(
+            *write_buffer,            // screen buffer to write to
+            copy_buffer.as_mut_ptr(), // buffer to copy into
+            buffer_size,              // col-row size of chiBuffer
+            buffer_coord,             // top left dest. cell in chiBuffer
+            source_buffer,            // screen buffer source rectangle
+        ))
     }
 }
 
-/// Parse integer to an bool
-#[inline]
-fn is_true(value: i32) -> bool {
-    value != 0
+/// Read the next input record from the console input buffer, this call blocks until an input record is available.
+pub fn read_console_input() -> io::Result<INPUT_RECORD> {
+    use winapi::um::consoleapi::ReadConsoleInputW;
+
+    let input_handle = get_input_handle()?;
+    let mut record: INPUT_RECORD = unsafe { mem::zeroed() };
+    let mut records_read: DWORD = 0;
+
+    unsafe {
+        result(ReadConsoleInputW(
+            input_handle,
+            &mut record,
+            1,
+            &mut records_read,
+        ))?;
+    }
+
+    Ok(record)
+}
+
+/// Wait until the console input buffer has input records or until the given timeout elapsed.
+///
+/// Returns whether input records are available.
+pub fn wait_for_console_input(timeout: Duration) -> io::Result<bool> {
+    use winapi::shared::winerror::WAIT_TIMEOUT;
+    use winapi::um::synchapi::WaitForSingleObject;
+    use winapi::um::winbase::WAIT_OBJECT_0;
+
+    let input_handle = get_input_handle()?;
+    // INFINITE (0xFFFFFFFF) is not a valid timeout here.
+    let millis = timeout.as_millis().min(0xFFFF_FFFE) as u32;
+
+    match unsafe { WaitForSingleObject(input_handle, millis) } {
+        WAIT_OBJECT_0 => Ok(true),
+        WAIT_TIMEOUT => Ok(false),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Turn the return value of an console function into an result, the error of the os is returned when it failed.
+///
+/// The console functions fail with `ERROR_INVALID_HANDLE` when the handle is not a console, for example when the output is redirected to a file.
+/// This is reported as an `ErrorKind::NotATerminal` error.
+fn result(value: i32) -> io::Result<()> {
+    if value != 0 {
+        return Ok(());
+    }
+
+    let error = io::Error::last_os_error();
+
+    if error.raw_os_error() == Some(ERROR_INVALID_HANDLE as i32) {
+        Err(Error::new(ErrorKind::NotATerminal, "the handle is not a console").into())
+    } else {
+        Err(error)
+    }
 }
@@ -1,8 +1,17 @@
+use std::io;
+
+/// Check whether stdout is an console, the console mode can only be read from an console.
+pub fn stdout_is_tty() -> bool {
+    super::kernel::get_output_handle()
+        .and_then(|handle| super::kernel::get_console_mode(&handle))
+        .is_ok()
+}
+
 /// Get the terminal size
-pub fn terminal_size() -> (u16, u16) {
-    let csbi = super::kernel::get_console_screen_buffer_info();
-    (
+pub fn terminal_size() -> io::Result<(u16, u16)> {
+    let csbi = super::kernel::get_console_screen_buffer_info()?;
+    Ok((
         (csbi.srWindow.Right - csbi.srWindow.Left) as u16,
         (csbi.srWindow.Bottom - csbi.srWindow.Top) as u16,
-    )
+    ))
 }
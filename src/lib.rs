@@ -2,6 +2,9 @@
 //! Crossterm aims to be simple and easy to call in code.
 //! True the simplicity of Crossterm you do not have to worry about the platform your working with.
 //! You can just call the action you want to perform and under water it will check what to do based on the current platform.
+//!
+//! With the optional `serde` feature the colors, attributes, styles and clear types can be (de)serialized,
+//! for example to store an theme in an configuration file.
 
 #[macro_use]
 mod shared;
@@ -9,11 +12,20 @@ mod kernel;
 mod state;
 
 pub mod cursor;
+pub mod input;
 pub mod style;
 pub mod terminal;
+pub mod vt;
 
 use shared::traits::Construct;
-pub use state::Context;
+#[doc(hidden)]
+pub use shared::command::{queue_command, OutputProbe, ProbeOutputTarget};
+pub use shared::command::{OutputTarget, Print, QueueableCommand};
+pub use shared::error::{Error, ErrorKind};
+pub use shared::traits::TerminalOutput;
+pub use state::{ChangeKind, Context, Crossterm, RestoreGuard, StateKey};
+#[cfg(unix)]
+pub use state::SuspendHandler;
 
 #[cfg(unix)]
 extern crate libc;
@@ -22,4 +34,8 @@ extern crate termios;
 #[cfg(windows)]
 extern crate winapi;
 
-extern crate rand;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+extern crate unicode_width;
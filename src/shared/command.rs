@@ -0,0 +1,141 @@
+//! This module contains the commands that can be queued with the `queue!` macro and executed with the `execute!` macro.
+//!
+//! Every cursor movement or color change that is written directly is a separate write to the output.
+//! With `queue!` the commands are only written to the buffer of the output, so many commands can be written at once with an single `flush()`.
+//! Use an buffered output like `BufWriter` to benefit from this, stdout is flushed by the standard library after every new line.
+//!
+//! The commands are defined in the module they belong to, like `cursor::Goto`, `terminal::Clear` and `style::SetFg`.
+
+#[cfg(windows)]
+use kernel::windows_kernel::ansi_support;
+use shared::traits::TerminalOutput;
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+/// This trait defines an command that can be written to an output with the `queue!` and `execute!` macros.
+///
+/// The command is written as `ANSI escape code` to the output,
+/// on windows consoles that do not support `ANSI escape codes` the command is executed with the `WINAPI` instead.
+pub trait QueueableCommand {
+    /// Write the `ANSI escape code` of this command to the given output.
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()>;
+
+    /// Write the `ANSI escape code` of this command to the given output that writes to the given target,
+    /// for the commands that depend on the output, like the colors that are converted to the color support of the output.
+    fn write_ansi_to(&self, output: &mut dyn Write, _target: OutputTarget) -> io::Result<()> {
+        self.write_ansi(output)
+    }
+
+    /// Execute this command with the `WINAPI`, the output is flushed first so that everything queued before this command is shown.
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Queue the given command on the given output that writes to the given target, this is used by the `queue!` macro.
+#[doc(hidden)]
+pub fn queue_command<W: Write, C: QueueableCommand>(
+    output: &mut W,
+    target: OutputTarget,
+    command: &C,
+) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        if !ansi_support::try_enable_ansi_support() {
+            return command.execute_winapi(output);
+        }
+    }
+
+    command.write_ansi_to(output, target)
+}
+
+/// What is known about the output an command is written to, see `TerminalOutput`.
+///
+/// The `queue!` and `execute!` macros treat an output that does not implement `TerminalOutput`,
+/// like an output of an generic type `W: Write`, as stdout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OutputTarget {
+    /// Whether the output writes to an terminal.
+    pub is_tty: bool,
+    /// Whether the output writes to stdout.
+    pub is_stdout: bool,
+}
+
+impl OutputTarget {
+    /// Get the target of the given output.
+    pub fn of<T: TerminalOutput + ?Sized>(output: &T) -> OutputTarget {
+        OutputTarget {
+            is_tty: output.is_tty(),
+            is_stdout: output.is_stdout(),
+        }
+    }
+
+    /// Get the target of stdout, whether stdout is an terminal is checked the first time it is used.
+    pub fn stdout() -> OutputTarget {
+        static STDOUT_IS_TTY: OnceLock<bool> = OnceLock::new();
+
+        OutputTarget {
+            is_tty: *STDOUT_IS_TTY.get_or_init(|| io::stdout().is_tty()),
+            is_stdout: true,
+        }
+    }
+}
+
+/// Wraps the output of the `queue!` and `execute!` macros to get its `OutputTarget`, this is used by those macros.
+#[doc(hidden)]
+pub struct OutputProbe<'a, W: 'a + ?Sized>(pub &'a W);
+
+/// Get the `OutputTarget` of the output in an `OutputProbe`, this is used by the `queue!` and `execute!` macros.
+///
+/// The implementation for the outputs that implement `TerminalOutput` takes the probe itself by reference,
+/// so it is picked before the one for all other outputs that takes an reference to the probe by reference.
+#[doc(hidden)]
+pub trait ProbeOutputTarget {
+    fn output_target(&self) -> OutputTarget;
+}
+
+impl<'a, W: TerminalOutput> ProbeOutputTarget for OutputProbe<'a, W> {
+    fn output_target(&self) -> OutputTarget {
+        OutputTarget::of(self.0)
+    }
+}
+
+impl<'a, 'b, W: ?Sized> ProbeOutputTarget for &'b OutputProbe<'a, W> {
+    fn output_target(&self) -> OutputTarget {
+        OutputTarget::stdout()
+    }
+}
+
+/// Command that prints the given displayable value.
+///
+/// #Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate crossterm;
+///
+/// use self::crossterm::cursor::Goto;
+/// use self::crossterm::Print;
+///
+/// fn main() {
+///     let mut buffer = Vec::new();
+///     queue!(buffer, Goto(5, 2), Print("@")).unwrap();
+///
+///     assert_eq!(buffer, b"\x1B[3;6H@");
+/// }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Print<D: Display>(pub D);
+
+impl<D: Display> QueueableCommand for Print<D> {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, "{}", self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        // the text is written to the output even when the console does not support `ANSI escape codes`.
+        write!(output, "{}", self.0)
+    }
+}
@@ -0,0 +1,106 @@
+//! This module contains the error type of crossterm.
+//!
+//! All crossterm functions return an `io::Result`, so errors can be propagated with `?` together with any other io error.
+//! The errors crossterm detects itself, like the output not being a terminal, carry an `Error` as inner error.
+//! Use `Error::kind_of()` to find out which of those errors occurred.
+
+use std::{error, fmt, io};
+
+/// Enum that represents the kinds of errors crossterm detects itself.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The input or output is not a terminal, for example because it is redirected to a file or pipe.
+    NotATerminal,
+    /// The terminal did not report the cursor position.
+    CursorPositionUnavailable,
+    /// An argument is out of the range the terminal supports, like an terminal size of 0.
+    OutOfRange,
+    /// The action is not supported on this platform or by this terminal.
+    Unsupported,
+    /// The input could not be decoded.
+    InvalidInput,
+    /// The input is closed.
+    InputClosed,
+    /// The mode is already enabled, like raw mode or the alternate screen.
+    AlreadyEnabled,
+}
+
+impl ErrorKind {
+    /// Get the `io::ErrorKind` an `io::Error` with this kind of error is created with.
+    fn io_kind(&self) -> io::ErrorKind {
+        match *self {
+            ErrorKind::CursorPositionUnavailable => io::ErrorKind::TimedOut,
+            ErrorKind::OutOfRange => io::ErrorKind::InvalidInput,
+            ErrorKind::InvalidInput => io::ErrorKind::InvalidData,
+            ErrorKind::InputClosed => io::ErrorKind::UnexpectedEof,
+            ErrorKind::AlreadyEnabled => io::ErrorKind::AlreadyExists,
+            ErrorKind::NotATerminal | ErrorKind::Unsupported => io::ErrorKind::Other,
+        }
+    }
+}
+
+/// Struct that represents an error detected by crossterm.
+///
+/// This error is returned inside an `io::Error`, use `Error::kind_of()` to get its kind.
+///
+/// #Example
+///
+/// ```rust
+///
+/// extern crate crossterm;
+///
+/// use self::crossterm::terminal;
+/// use self::crossterm::{Error, ErrorKind};
+///
+/// match terminal::terminal().terminal_size() {
+///     Ok((width, height)) => println!("The terminal is {} by {} cells", width, height),
+///     Err(ref e) if Error::kind_of(e) == Some(ErrorKind::NotATerminal) => {
+///         println!("The output is not a terminal, using the default size");
+///     }
+///     Err(e) => println!("Error: {}", e),
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl Error {
+    /// Create new error of the given kind with an message describing what went wrong.
+    pub fn new<M: Into<String>>(kind: ErrorKind, message: M) -> Error {
+        Error {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Get the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Get the kind of the crossterm error inside the given `io::Error`,
+    /// `None` is returned when the `io::Error` is not created from an crossterm error, like an error from the os.
+    pub fn kind_of(error: &io::Error) -> Option<ErrorKind> {
+        error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<Error>())
+            .map(Error::kind)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> io::Error {
+        io::Error::new(error.kind.io_kind(), error)
+    }
+}
@@ -1,11 +1,13 @@
 //! Some actions need to preformed platform independently since they can not be solved `ANSI escape codes`.
 
-use Context;
+use {Error, ErrorKind};
+
+use std::io;
 
 #[cfg(unix)]
-use kernel::unix_kernel::terminal::terminal_size;
+use kernel::unix_kernel::terminal::{stdout_is_tty, terminal_size};
 #[cfg(windows)]
-use kernel::windows_kernel::terminal::terminal_size;
+use kernel::windows_kernel::terminal::{stdout_is_tty, terminal_size};
 
 #[cfg(unix)]
 use kernel::unix_kernel::terminal::pos;
@@ -13,15 +15,28 @@ use kernel::unix_kernel::terminal::pos;
 use kernel::windows_kernel::cursor::pos;
 
 /// Get the terminal size based on the current platform.
-pub fn get_terminal_size() -> (u16, u16) {
+pub fn get_terminal_size() -> io::Result<(u16, u16)> {
     terminal_size()
 }
 
+/// Check whether stdout is an terminal based on the current platform.
+pub fn is_stdout_tty() -> bool {
+    stdout_is_tty()
+}
+
 /// Get the cursor position based on the current platform.
-pub fn get_cursor_position() -> (u16, u16) {
+pub fn get_cursor_position() -> io::Result<(u16, u16)> {
     pos()
 }
 
+/// The error that is returned when there is no implementation for an action on the current platform.
+pub fn not_supported() -> io::Error {
+    Error::new(
+        ErrorKind::Unsupported,
+        "this action is not supported on this platform",
+    ).into()
+}
+
 #[cfg(windows)]
 /// Get an module specific implementation based on the current platform.
 pub fn get_module<T>(winapi_impl: T, unix_impl: T) -> Option<T> {
@@ -3,3 +3,84 @@
 macro_rules! csi {
     ($( $l:expr ),*) => { concat!("\x1B[", $( $l ),*) };
 }
+
+/// Queue one or more commands on the given output, the commands are written when the output is flushed.
+///
+/// The commands are written in the given order, when writing an command fails the commands after it are not written and the error is returned.
+/// The colors are written for the color support of the output when it implements `TerminalOutput`, otherwise for the color support of stdout.
+///
+/// #Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate crossterm;
+///
+/// use self::crossterm::cursor::Goto;
+/// use self::crossterm::style::{Color, ResetColor, SetFg};
+/// use self::crossterm::Print;
+/// use std::io::{stdout, BufWriter, Write};
+///
+/// fn main() {
+///     let mut output = BufWriter::new(stdout());
+///
+///     // nothing is written yet.
+///     queue!(output, Goto(10, 5), SetFg(Color::Red), Print("Red text"), ResetColor).unwrap();
+///     queue!(output, Goto(10, 6), Print("Normal text")).unwrap();
+///
+///     // all commands are written at once.
+///     output.flush().unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! queue {
+    ($output:expr, $($command:expr),+ $(,)*) => {{
+        let output = &mut $output;
+        let target = {
+            use $crate::ProbeOutputTarget;
+            (&$crate::OutputProbe(&*output)).output_target()
+        };
+        let mut result: ::std::io::Result<()> = Ok(());
+        $(
+            if result.is_ok() {
+                result = $crate::queue_command(output, target, &$command);
+            }
+        )+
+        result
+    }};
+}
+
+/// Write one or more commands to the given output and flush it, so that the commands are executed right away.
+///
+/// This is the same as `queue!` followed by an `flush()` of the output.
+///
+/// #Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate crossterm;
+///
+/// use self::crossterm::terminal::{Clear, ClearType};
+/// use self::crossterm::cursor::Goto;
+/// use std::io::stdout;
+///
+/// fn main() {
+///     execute!(stdout(), Clear(ClearType::All), Goto(0, 0)).unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! execute {
+    ($output:expr, $($command:expr),+ $(,)*) => {{
+        let output = &mut $output;
+        let target = {
+            use $crate::ProbeOutputTarget;
+            (&$crate::OutputProbe(&*output)).output_target()
+        };
+        let mut result: ::std::io::Result<()> = Ok(());
+        $(
+            if result.is_ok() {
+                result = $crate::queue_command(output, target, &$command);
+            }
+        )+
+        result.and_then(|_| ::std::io::Write::flush(output))
+    }};
+}
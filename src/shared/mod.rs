@@ -2,5 +2,7 @@
 
 #[macro_use]
 pub mod macros;
+pub mod command;
+pub mod error;
 pub mod functions;
 pub mod traits;
@@ -1,3 +1,8 @@
+use std::fs::File;
+use std::io::{
+    BufWriter, Cursor, IsTerminal, LineWriter, Sink, Stderr, StderrLock, Stdout, StdoutLock, Write,
+};
+
 /// This trait is used for creating an instance of an concrete implementation from an base trait.
 /// This trait allows the output to be different in size.
 pub trait Construct {
@@ -7,6 +12,130 @@ pub trait Construct {
 }
 
 /// This trait can be used to create an empty instance of a struct.
+#[cfg(windows)]
 pub trait Empty {
     fn empty() -> Self;
 }
+
+/// This trait tells whether an output writes to an terminal.
+///
+/// The color support and style policy are detected for the output the styling is written to, instead of for stdout.
+/// It is implemented for the standard streams, files, in-memory buffers and the writers that wrap them,
+/// implement it for your own output to use it with `TerminalColor::from_output()`.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::TerminalOutput;
+/// use std::io::{stdout, BufWriter};
+///
+/// assert!(!Vec::new().is_tty());
+/// assert!(BufWriter::new(stdout()).is_stdout());
+/// ```
+pub trait TerminalOutput {
+    /// Check whether this output writes to an terminal.
+    fn is_tty(&self) -> bool;
+
+    /// Check whether this output writes to stdout, which uses the global color support and style policy.
+    fn is_stdout(&self) -> bool {
+        false
+    }
+}
+
+impl TerminalOutput for Stdout {
+    fn is_tty(&self) -> bool {
+        self.is_terminal()
+    }
+
+    fn is_stdout(&self) -> bool {
+        true
+    }
+}
+
+impl<'a> TerminalOutput for StdoutLock<'a> {
+    fn is_tty(&self) -> bool {
+        self.is_terminal()
+    }
+
+    fn is_stdout(&self) -> bool {
+        true
+    }
+}
+
+impl TerminalOutput for Stderr {
+    fn is_tty(&self) -> bool {
+        self.is_terminal()
+    }
+}
+
+impl<'a> TerminalOutput for StderrLock<'a> {
+    fn is_tty(&self) -> bool {
+        self.is_terminal()
+    }
+}
+
+impl TerminalOutput for File {
+    fn is_tty(&self) -> bool {
+        self.is_terminal()
+    }
+}
+
+impl TerminalOutput for Vec<u8> {
+    fn is_tty(&self) -> bool {
+        false
+    }
+}
+
+impl<T> TerminalOutput for Cursor<T> {
+    fn is_tty(&self) -> bool {
+        false
+    }
+}
+
+impl TerminalOutput for Sink {
+    fn is_tty(&self) -> bool {
+        false
+    }
+}
+
+impl<W: Write + TerminalOutput> TerminalOutput for BufWriter<W> {
+    fn is_tty(&self) -> bool {
+        self.get_ref().is_tty()
+    }
+
+    fn is_stdout(&self) -> bool {
+        self.get_ref().is_stdout()
+    }
+}
+
+impl<W: Write + TerminalOutput> TerminalOutput for LineWriter<W> {
+    fn is_tty(&self) -> bool {
+        self.get_ref().is_tty()
+    }
+
+    fn is_stdout(&self) -> bool {
+        self.get_ref().is_stdout()
+    }
+}
+
+impl<T: TerminalOutput + ?Sized> TerminalOutput for &mut T {
+    fn is_tty(&self) -> bool {
+        (**self).is_tty()
+    }
+
+    fn is_stdout(&self) -> bool {
+        (**self).is_stdout()
+    }
+}
+
+impl<T: TerminalOutput + ?Sized> TerminalOutput for Box<T> {
+    fn is_tty(&self) -> bool {
+        (**self).is_tty()
+    }
+
+    fn is_stdout(&self) -> bool {
+        (**self).is_stdout()
+    }
+}
@@ -9,7 +9,7 @@
 //!
 //! See the `Context` struct where we store the commands for more info.
 
-use rand;
+use std::io;
 
 #[cfg(unix)]
 pub mod unix_command;
@@ -18,35 +18,33 @@ pub mod win_commands;
 
 pub mod shared_commands;
 
+pub use self::shared_commands::AlternateScreenCommand;
 #[cfg(unix)]
 pub use self::unix_command::*;
 
 #[cfg(windows)]
 pub use self::win_commands::*;
 
-use super::Context;
+use super::{ChangeKind, Context, StateKey};
 
 /// This command can be used for simple commands witch just have an `undo()` and an `execute()`
+#[cfg(windows)]
 pub trait ICommand {
     fn new() -> Box<Self>
     where
         Self: Sized;
-    fn execute(&mut self) -> bool;
-    fn undo(&mut self) -> bool;
+    fn execute(&mut self) -> io::Result<()>;
+    fn undo(&mut self) -> io::Result<()>;
 }
 
 /// This command is used for complex commands whits change the terminal state.
 /// By passing an `Context` instance this command will register it self to notify the terminal state change.
-pub trait IContextCommand {
-    fn new(context: &mut Context) -> (Box<Self>, i16)
+pub trait IContextCommand: Send {
+    fn new(context: &mut Context) -> (Box<Self>, StateKey)
     where
         Self: Sized;
-    fn execute(&mut self) -> bool;
-    fn undo(&mut self) -> bool;
-}
-
-/// This generates an random key for the `ContextCommand`.
-/// So that we can identify the `ContextCommand` in an list of commands.
-fn generate_key() -> i16 {
-    rand::random::<i16>()
+    fn execute(&mut self) -> io::Result<()>;
+    fn undo(&mut self) -> io::Result<()>;
+    /// The kind of change this command makes.
+    fn kind(&self) -> ChangeKind;
 }
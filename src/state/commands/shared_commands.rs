@@ -1,31 +1,48 @@
 //! This module contains the commands that can be used for both unix and windows systems.
 
-use super::ICommand;
+use super::{ChangeKind, IContextCommand, StateKey};
+use state;
+use terminal::screen::{ToAlternateScreen, ToMainScreen};
+use Context;
+
 use std::io;
 use std::io::Write;
 
-/// This command is used for switching to alternate screen and back to main screen.
+/// This command is used for switching the terminal to the alternate screen and back to the main screen.
+///
+/// The switch is written to stdout, the terminal only switches back when it is still at the alternate screen,
+/// because an `AlternateScreen` switches back itself by writing to its own output.
 #[derive(Clone, Copy)]
-pub struct ToAlternateScreenBufferCommand;
+pub struct AlternateScreenCommand;
 
-impl ICommand for ToAlternateScreenBufferCommand {
-    fn new() -> Box<ToAlternateScreenBufferCommand> {
-        Box::from(ToAlternateScreenBufferCommand {})
+impl IContextCommand for AlternateScreenCommand {
+    fn new(context: &mut Context) -> (Box<AlternateScreenCommand>, StateKey) {
+        let command = AlternateScreenCommand;
+        let key = context.register_change(Box::from(command));
+        (Box::from(command), key)
     }
 
-    fn execute(&mut self) -> bool {
+    fn execute(&mut self) -> io::Result<()> {
         let mut some_writer = io::stdout();
-        match write!(some_writer, csi!("?1049h")) {
-            Ok(_) => true,
-            Err(_) => false,
-        }
+        write!(some_writer, "{}", ToAlternateScreen)?;
+        some_writer.flush()?;
+        state::set_alternate_screen(true);
+        Ok(())
     }
 
-    fn undo(&mut self) -> bool {
-        let mut some_writer = io::stdout();
-        match write!(some_writer, csi!("?1049l")) {
-            Ok(_) => true,
-            Err(_) => false,
+    fn undo(&mut self) -> io::Result<()> {
+        if !state::alternate_screen_active() {
+            return Ok(());
         }
+
+        let mut some_writer = io::stdout();
+        write!(some_writer, "{}", ToMainScreen)?;
+        some_writer.flush()?;
+        state::set_alternate_screen(false);
+        Ok(())
+    }
+
+    fn kind(&self) -> ChangeKind {
+        ChangeKind::AlternateScreen
     }
 }
@@ -1,100 +1,137 @@
 //! This module contains the commands that can be used for unix systems.
 
-use super::IContextCommand;
+use super::{ChangeKind, IContextCommand, StateKey};
 use kernel::unix_kernel::terminal;
-use termios::{tcsetattr, Termios, CREAD, ECHO, ICANON, TCSAFLUSH};
+use state;
+use termios::{Termios, ECHO, ICANON};
 use Context;
 
-const FD_STDIN: ::std::os::unix::io::RawFd = 1;
+use std::io::{self, Write};
 
 /// This command is used for switching to NoncanonicalMode.
 #[derive(Clone, Copy)]
 pub struct NoncanonicalModeCommand {
-    key: i16,
+    /// The terminal mode before noncanonical mode is enabled, this is read when the command is created
+    /// so that the copy that is registered in the context can also restore it.
+    original_mode: Option<Termios>,
 }
 
 impl IContextCommand for NoncanonicalModeCommand {
-    fn new(context: &mut Context) -> (Box<NoncanonicalModeCommand>, i16) {
-        let key = super::generate_key();
-        let command = NoncanonicalModeCommand { key: key };
-        context.register_change(Box::from(command), key);
+    fn new(context: &mut Context) -> (Box<NoncanonicalModeCommand>, StateKey) {
+        let command = NoncanonicalModeCommand {
+            original_mode: terminal::get_terminal_mode().ok(),
+        };
+        let key = context.register_change(Box::from(command));
         (Box::from(command), key)
     }
 
-    fn execute(&mut self) -> bool {
+    fn execute(&mut self) -> io::Result<()> {
         // Set noncanonical mode
-        if let Ok(orig) = Termios::from_fd(FD_STDIN) {
-            let mut noncan = orig.clone();
-            noncan.c_lflag &= !ICANON;
-            noncan.c_lflag &= !ECHO;
-            noncan.c_lflag &= !CREAD;
-            match tcsetattr(FD_STDIN, TCSAFLUSH, &noncan) {
-                Ok(_) => return true,
-                Err(_) => return false,
-            };
-        } else {
-            return false;
-        }
+        let mut noncan = match self.original_mode {
+            Some(original_mode) => original_mode,
+            None => terminal::get_terminal_mode()?,
+        };
+        noncan.c_lflag &= !ICANON;
+        noncan.c_lflag &= !ECHO;
+        terminal::set_terminal_mode(&noncan)
     }
 
-    fn undo(&mut self) -> bool {
-        // Disable noncanonical mode
-        if let Ok(orig) = Termios::from_fd(FD_STDIN) {
-            let mut noncan = orig.clone();
-            noncan.c_lflag &= ICANON;
-            noncan.c_lflag &= ECHO;
-            noncan.c_lflag &= CREAD;
-
-            match tcsetattr(FD_STDIN, TCSAFLUSH, &noncan) {
-                Ok(_) => return true,
-                Err(_) => return false,
-            };
-        } else {
-            return false;
+    fn undo(&mut self) -> io::Result<()> {
+        // Disable noncanonical mode, when the original mode is unknown the mode could not have been changed.
+        match self.original_mode {
+            Some(ref original_mode) => terminal::set_terminal_mode(original_mode),
+            None => Ok(()),
         }
     }
+
+    fn kind(&self) -> ChangeKind {
+        ChangeKind::NoncanonicalMode
+    }
 }
 
 /// This command is used for enabling and disabling raw mode for the terminal.
 #[derive(Clone, Copy)]
 pub struct EnableRawModeCommand {
+    /// The terminal mode before raw mode is enabled, this is read when the command is created
+    /// so that the copy that is registered in the context can also restore it.
     original_mode: Option<Termios>,
-    key: i16,
 }
 
 impl IContextCommand for EnableRawModeCommand {
-    fn new(context: &mut Context) -> (Box<EnableRawModeCommand>, i16) {
-        let key = super::generate_key();
+    fn new(context: &mut Context) -> (Box<EnableRawModeCommand>, StateKey) {
         let command = EnableRawModeCommand {
-            original_mode: None,
-            key: key,
+            original_mode: terminal::get_terminal_mode().ok(),
         };
-        context.register_change(Box::from(command), key);
+        let key = context.register_change(Box::from(command));
         (Box::from(command), key)
     }
 
-    fn execute(&mut self) -> bool {
-        if let Ok(original_mode) = terminal::get_terminal_mode() {
-            self.original_mode = Some(original_mode);
-            let mut new_mode = self.original_mode.unwrap();
-            terminal::make_raw(&mut new_mode);
-            terminal::set_terminal_mode(&new_mode);
-            true
-        } else {
-            return false;
-        }
+    fn execute(&mut self) -> io::Result<()> {
+        let mut new_mode = match self.original_mode {
+            Some(original_mode) => original_mode,
+            None => terminal::get_terminal_mode()?,
+        };
+        terminal::make_raw(&mut new_mode);
+        terminal::set_terminal_mode(&new_mode)?;
+        state::set_raw_mode(true);
+        Ok(())
     }
 
-    fn undo(&mut self) -> bool {
-        if let Ok(original_mode) = terminal::get_terminal_mode() {
-            let result = terminal::set_terminal_mode(&self.original_mode.unwrap());
-
-            match result {
-                Ok(()) => true,
-                Err(_) => false,
+    fn undo(&mut self) -> io::Result<()> {
+        // When the original mode is unknown raw mode could not have been enabled.
+        match self.original_mode {
+            Some(ref original_mode) => {
+                terminal::set_terminal_mode(original_mode)?;
+                state::set_raw_mode(false);
+                Ok(())
             }
-        } else {
-            return false;
+            None => Ok(()),
         }
     }
+
+    fn kind(&self) -> ChangeKind {
+        ChangeKind::RawMode
+    }
+}
+
+/// This command is used for enabling and disabling mouse capture.
+///
+/// It enables normal (`?1000h`), button event (`?1002h`) and any event (`?1003h`) mouse tracking,
+/// so that presses, releases, drags and moves are reported. The coordinates are reported with the `SGR` (`?1006h`) extended encoding,
+/// terminals that do not support this encoding fall back to the `X10` encoding.
+#[derive(Clone, Copy)]
+pub struct EnableMouseModeCommand;
+
+impl IContextCommand for EnableMouseModeCommand {
+    fn new(context: &mut Context) -> (Box<EnableMouseModeCommand>, StateKey) {
+        let command = EnableMouseModeCommand;
+        let key = context.register_change(Box::from(command));
+        (Box::from(command), key)
+    }
+
+    fn execute(&mut self) -> io::Result<()> {
+        let mut some_writer = io::stdout();
+        write!(
+            some_writer,
+            concat!(csi!("?1000h"), csi!("?1002h"), csi!("?1003h"), csi!("?1006h"))
+        )?;
+        some_writer.flush()?;
+        state::set_mouse_capture(true);
+        Ok(())
+    }
+
+    fn undo(&mut self) -> io::Result<()> {
+        let mut some_writer = io::stdout();
+        write!(
+            some_writer,
+            concat!(csi!("?1006l"), csi!("?1003l"), csi!("?1002l"), csi!("?1000l"))
+        )?;
+        some_writer.flush()?;
+        state::set_mouse_capture(false);
+        Ok(())
+    }
+
+    fn kind(&self) -> ChangeKind {
+        ChangeKind::MouseCapture
+    }
 }
@@ -1,10 +1,12 @@
 //! This module contains the commands that can be used for windows systems.
 
 use super::super::Context;
-use super::{ICommand, IContextCommand};
+use super::{ChangeKind, ICommand, IContextCommand, StateKey};
+use state;
+use {Error, ErrorKind};
 
 use kernel::windows_kernel::{ansi_support, kernel};
-use std::mem;
+use std::{io, mem};
 use winapi::shared::minwindef::DWORD;
 use winapi::um::wincon;
 use winapi::um::wincon::{CHAR_INFO, COORD, ENABLE_VIRTUAL_TERMINAL_PROCESSING, SMALL_RECT};
@@ -18,48 +20,36 @@ pub struct EnableAnsiCommand {
 
 impl ICommand for EnableAnsiCommand {
     fn new() -> Box<Self> {
-        let key = super::generate_key();
         let command = EnableAnsiCommand {
             mask: ENABLE_VIRTUAL_TERMINAL_PROCESSING,
         };
         Box::from(command)
     }
 
-    fn execute(&mut self) -> bool {
+    fn execute(&mut self) -> io::Result<()> {
         // we need to check whether we tried to enable ansi before. If we have we can just return if that had succeeded.
         if ansi_support::has_been_tried_to_enable() && ansi_support::ansi_enabled() {
-            ansi_support::windows_supportable()
-        } else {
-            let output_handle = kernel::get_output_handle();
-            let mut dw_mode: DWORD = 0;
-
-            if !kernel::get_console_mode(&output_handle, &mut dw_mode) {
-                return false;
+            if ansi_support::windows_supportable() {
+                Ok(())
+            } else {
+                Err(Error::new(ErrorKind::Unsupported, "this console does not support ANSI escape codes").into())
             }
-
-            dw_mode |= self.mask;
-
+        } else {
+            let output_handle = kernel::get_output_handle()?;
+            let dw_mode = kernel::get_console_mode(&output_handle)? | self.mask;
             kernel::set_console_mode(&output_handle, dw_mode)
         }
     }
 
-    fn undo(&mut self) -> bool {
+    fn undo(&mut self) -> io::Result<()> {
         if ansi_support::ansi_enabled() {
-            let output_handle = kernel::get_output_handle();
-
-            let mut dw_mode: DWORD = 0;
-            if !kernel::get_console_mode(&output_handle, &mut dw_mode) {
-                return false;
-            }
-
-            dw_mode &= !self.mask;
-            if !kernel::set_console_mode(&output_handle, dw_mode) {
-                return false;
-            }
+            let output_handle = kernel::get_output_handle()?;
+            let dw_mode = kernel::get_console_mode(&output_handle)? & !self.mask;
+            kernel::set_console_mode(&output_handle, dw_mode)?;
 
             ansi_support::set_ansi_enabled(false);
         }
-        return true;
+        Ok(())
     }
 }
 
@@ -71,41 +61,90 @@ pub struct EnableRawModeCommand {
 }
 
 impl IContextCommand for EnableRawModeCommand {
-    fn new(context: &mut Context) -> (Box<EnableRawModeCommand>, i16) {
+    fn new(context: &mut Context) -> (Box<EnableRawModeCommand>, StateKey) {
         use self::wincon::{ENABLE_ECHO_INPUT, ENABLE_LINE_INPUT, ENABLE_PROCESSED_INPUT};
 
-        let key = super::generate_key();
         let command = EnableRawModeCommand {
             mask: ENABLE_LINE_INPUT | ENABLE_PROCESSED_INPUT | ENABLE_ECHO_INPUT,
         };
-        context.register_change(Box::from(command), key);
+        let key = context.register_change(Box::from(command));
         (Box::from(command), key)
     }
 
-    fn execute(&mut self) -> bool {
-        let input_handle = kernel::get_input_handle();
+    fn execute(&mut self) -> io::Result<()> {
+        let input_handle = kernel::get_input_handle()?;
+        let dw_mode = kernel::get_console_mode(&input_handle)? & !self.mask;
+        kernel::set_console_mode(&input_handle, dw_mode)?;
+        state::set_raw_mode(true);
+        Ok(())
+    }
 
-        let mut dw_mode: DWORD = 0;
-        if !kernel::get_console_mode(&input_handle, &mut dw_mode) {
-            return false;
-        }
+    fn undo(&mut self) -> io::Result<()> {
+        let input_handle = kernel::get_input_handle()?;
+        let dw_mode = kernel::get_console_mode(&input_handle)? | self.mask;
+        kernel::set_console_mode(&input_handle, dw_mode)?;
+        state::set_raw_mode(false);
+        Ok(())
+    }
 
-        dw_mode &= !self.mask;
+    fn kind(&self) -> ChangeKind {
+        ChangeKind::RawMode
+    }
+}
+
+/// This command is used for enabling and disabling mouse capture for windows systems.
+/// Quick edit mode is disabled while mouse capture is enabled, otherwise the console uses the mouse for selecting text.
+/// For more info check: https://docs.microsoft.com/en-us/windows/console/setconsolemode.
+#[derive(Clone, Copy)]
+pub struct EnableMouseModeCommand {
+    original_mode: Option<DWORD>,
+}
 
-        kernel::set_console_mode(&input_handle, dw_mode)
+impl IContextCommand for EnableMouseModeCommand {
+    fn new(context: &mut Context) -> (Box<EnableMouseModeCommand>, StateKey) {
+        let original_mode = kernel::get_input_handle()
+            .and_then(|handle| kernel::get_console_mode(&handle))
+            .ok();
+        let command = EnableMouseModeCommand { original_mode };
+        let key = context.register_change(Box::from(command));
+        (Box::from(command), key)
     }
 
-    fn undo(&mut self) -> bool {
-        let output_handle = kernel::get_output_handle();
+    fn execute(&mut self) -> io::Result<()> {
+        use self::wincon::{ENABLE_EXTENDED_FLAGS, ENABLE_MOUSE_INPUT, ENABLE_QUICK_EDIT_MODE};
 
-        let mut dw_mode: DWORD = 0;
-        if !kernel::get_console_mode(&output_handle, &mut dw_mode) {
-            return false;
-        }
+        let input_handle = kernel::get_input_handle()?;
+        let original_mode = match self.original_mode {
+            Some(original_mode) => original_mode,
+            None => kernel::get_console_mode(&input_handle)?,
+        };
+        let dw_mode =
+            (original_mode | ENABLE_MOUSE_INPUT | ENABLE_EXTENDED_FLAGS) & !ENABLE_QUICK_EDIT_MODE;
+        kernel::set_console_mode(&input_handle, dw_mode)?;
+        state::set_mouse_capture(true);
+        Ok(())
+    }
 
-        dw_mode |= self.mask;
+    fn undo(&mut self) -> io::Result<()> {
+        use self::wincon::ENABLE_EXTENDED_FLAGS;
+
+        match self.original_mode {
+            // the extended flags need to be set for quick edit mode to be restored.
+            Some(original_mode) => {
+                kernel::set_console_mode(
+                    &kernel::get_input_handle()?,
+                    original_mode | ENABLE_EXTENDED_FLAGS,
+                )?;
+                state::set_mouse_capture(false);
+                Ok(())
+            }
+            // When the original mode is unknown mouse capture could not have been enabled.
+            None => Ok(()),
+        }
+    }
 
-        kernel::set_console_mode(&output_handle, dw_mode)
+    fn kind(&self) -> ChangeKind {
+        ChangeKind::MouseCapture
     }
 }
 
@@ -119,16 +158,16 @@ impl ICommand for ToAlternateScreenBufferCommand {
         Box::from(ToAlternateScreenBufferCommand {})
     }
 
-    fn execute(&mut self) -> bool {
+    fn execute(&mut self) -> io::Result<()> {
         let mut chi_buffer: [CHAR_INFO; 160] = unsafe { mem::zeroed() };
 
-        let handle = kernel::get_output_handle();
+        let handle = kernel::get_output_handle()?;
 
         // create a new screen buffer to copy to.
-        let new_handle = kernel::create_console_screen_buffer();
+        let new_handle = kernel::create_console_screen_buffer()?;
 
         // Make the new screen buffer the active screen buffer.
-        kernel::set_active_screen_buffer(new_handle);
+        kernel::set_active_screen_buffer(new_handle)?;
 
         // Set the source rectangle.
         let mut srct_read_rect = SMALL_RECT {
@@ -152,7 +191,7 @@ impl ICommand for ToAlternateScreenBufferCommand {
             coord_buffer_size,
             coord_buffer_coord,
             &mut srct_read_rect,
-        );
+        )?;
 
         // Set the destination rectangle.
         let mut srct_write_rect = SMALL_RECT {
@@ -169,14 +208,11 @@ impl ICommand for ToAlternateScreenBufferCommand {
             coord_buffer_size,
             coord_buffer_coord,
             &mut srct_write_rect,
-        );
-
-        true
+        )
     }
 
-    fn undo(&mut self) -> bool {
-        let handle = kernel::get_output_handle();
-        kernel::set_active_screen_buffer(handle);
-        true
+    fn undo(&mut self) -> io::Result<()> {
+        let handle = kernel::get_output_handle()?;
+        kernel::set_active_screen_buffer(handle)
     }
 }
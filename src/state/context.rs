@@ -1,41 +1,256 @@
 //! This module is used for registering, storing an restoring the terminal state changes.
 
 use super::commands::IContextCommand;
-use std::collections::HashMap;
-use std::ops::Drop;
+
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+
+/// Key of an state change that is registered in an `Context`, used to undo that specific change.
+///
+/// The keys are handed out by the context in the order the changes are registered, so they never collide.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StateKey(u64);
+
+/// The kind of change that is registered in an `Context`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// Raw mode is enabled.
+    RawMode,
+    /// Noncanonical mode is enabled, for example while the cursor position is read.
+    NoncanonicalMode,
+    /// Mouse capture is enabled.
+    MouseCapture,
+    /// The terminal switched to the alternate screen.
+    AlternateScreen,
+}
 
 /// Struct that stores the changed states of the terminal.
+///
+/// The changes are restored in the reverse order they are registered,
+/// so the terminal goes back through the same states it went through when the changes were made.
+///
+/// An clone of the context shares the changes with the original, so the changes can be restored from
+/// another place than where they are made, like an `RestoreGuard` does when the program panics.
+/// The context that is shared by the whole process can be obtained from an `Crossterm` handle.
+#[derive(Clone)]
 pub struct Context {
-    changed_states: HashMap<i16, Box<IContextCommand>>,
+    states: Arc<Mutex<ChangedStates>>,
+}
+
+/// The changes of an context, shared by its clones.
+struct ChangedStates {
+    changes: Vec<(StateKey, Box<dyn IContextCommand>)>,
+    next_key: u64,
+}
+
+impl ChangedStates {
+    /// Restore all changes, the last registered change first.
+    fn restore(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+
+        while let Some((_, mut state)) = self.changes.pop() {
+            let undone = state.undo();
+
+            if result.is_ok() {
+                result = undone;
+            }
+        }
+
+        result
+    }
 }
 
 impl Context {
     /// Create new Context where the terminals states can be handled.
     pub fn new() -> Context {
-        println!("Context has been created");
         Context {
-            changed_states: HashMap::new(),
+            states: Arc::new(Mutex::new(ChangedStates {
+                changes: Vec::new(),
+                next_key: 0,
+            })),
         }
     }
 
-    /// Restore all changes that are made to the terminal.
-    pub fn restore_changes(&mut self) {
-        for (x, state) in self.changed_states.iter_mut() {
-            state.undo();
+    /// Check whether both contexts share the same changes, because one is an clone of the other.
+    #[cfg(unix)]
+    pub(crate) fn shares_changes_with(&self, other: &Context) -> bool {
+        Arc::ptr_eq(&self.states, &other.states)
+    }
+
+    /// Lock the changes, an change that panicked while it was restored is still in a usable state.
+    fn states(&self) -> MutexGuard<'_, ChangedStates> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Restore all changes that are made to the terminal, the last registered change is restored first.
+    ///
+    /// All changes are restored even when restoring one of them fails, the first error is returned.
+    /// The restored changes are removed, so they are not restored again.
+    pub fn restore_changes(&mut self) -> io::Result<()> {
+        self.states().restore()
+    }
+
+    /// Restore all changes without waiting for the changes to be unlocked,
+    /// nothing is restored when they are in use, for example by the thread that panicked while it used them.
+    pub(crate) fn try_restore_changes(&self) -> io::Result<()> {
+        match self.states.try_lock() {
+            Ok(mut states) => states.restore(),
+            Err(TryLockError::Poisoned(e)) => e.into_inner().restore(),
+            Err(TryLockError::WouldBlock) => Ok(()),
         }
     }
 
-    /// Register new changed state with the given key.
-    pub fn register_change(&mut self, change: Box<IContextCommand>, key: i16) {
-        if !self.changed_states.contains_key(&key) {
-            self.changed_states.insert(key, change);
+    /// Get the changes that are registered and not restored yet, in the order they are registered.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    /// extern crate crossterm;
+    /// use self::crossterm::{ChangeKind, Context};
+    /// use self::crossterm::terminal::screen::AlternateScreen;
+    /// use self::crossterm::Crossterm;
+    /// use std::io::stdout;
+    ///
+    /// // only an alternate screen on the terminal is registered.
+    /// let _screen = AlternateScreen::from(stdout()).unwrap();
+    ///
+    /// let changes = Crossterm::new().context().registrations();
+    /// assert!(changes.iter().any(|&(_, kind)| kind == ChangeKind::AlternateScreen));
+    /// assert!(Context::new().registrations().is_empty());
+    /// ```
+    pub fn registrations(&self) -> Vec<(StateKey, ChangeKind)> {
+        self.states()
+            .changes
+            .iter()
+            .map(|(key, change)| (*key, change.kind()))
+            .collect()
+    }
+
+    /// Undo all changes without removing them, the last registered change is undone first.
+    /// The changes are applied again with `resume_changes()`, for example when the process is suspended.
+    #[cfg(unix)]
+    pub(crate) fn suspend_changes(&self) -> io::Result<()> {
+        let mut result = Ok(());
+
+        for (_, change) in self.states().changes.iter_mut().rev() {
+            let undone = change.undo();
+
+            if result.is_ok() {
+                result = undone;
+            }
         }
+
+        result
     }
 
-    /// Undo an specific state by the given state key.
-    pub fn undo_state(&mut self, state_key: i16) {
-        if self.changed_states.contains_key(&state_key) {
-            self.changed_states.remove(&state_key);
+    /// Apply the changes that are undone with `suspend_changes()` again, in the order they are registered.
+    #[cfg(unix)]
+    pub(crate) fn resume_changes(&self) -> io::Result<()> {
+        let mut result = Ok(());
+
+        for (_, change) in self.states().changes.iter_mut() {
+            let applied = change.execute();
+
+            if result.is_ok() {
+                result = applied;
+            }
         }
+
+        result
+    }
+
+    /// Register new changed state, the returned key can be used to undo this change with `undo_state()`.
+    pub fn register_change(&mut self, change: Box<dyn IContextCommand>) -> StateKey {
+        let mut states = self.states();
+
+        let key = StateKey(states.next_key);
+        states.next_key += 1;
+        states.changes.push((key, change));
+        key
+    }
+
+    /// Undo an specific state by the given state key, the other changes keep their order.
+    pub fn undo_state(&mut self, state_key: StateKey) -> io::Result<()> {
+        let mut change = {
+            let mut states = self.states();
+
+            match states.changes.iter().position(|&(key, _)| key == state_key) {
+                Some(index) => states.changes.remove(index).1,
+                None => return Ok(()),
+            }
+        };
+
+        change.undo()
+    }
+}
+
+impl Default for Context {
+    fn default() -> Context {
+        Context::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{ChangeKind, Context, StateKey};
+    use state::commands::IContextCommand;
+
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Change that records when it is applied and undone, under the given name.
+    pub(crate) struct RecordedChange(pub &'static str, pub Arc<Mutex<Vec<String>>>);
+
+    impl IContextCommand for RecordedChange {
+        fn new(_context: &mut Context) -> (Box<RecordedChange>, StateKey) {
+            unreachable!()
+        }
+
+        fn execute(&mut self) -> io::Result<()> {
+            self.1.lock().unwrap().push(format!("execute {}", self.0));
+            Ok(())
+        }
+
+        fn undo(&mut self) -> io::Result<()> {
+            self.1.lock().unwrap().push(format!("undo {}", self.0));
+            Ok(())
+        }
+
+        fn kind(&self) -> ChangeKind {
+            ChangeKind::RawMode
+        }
+    }
+
+    #[test]
+    fn restores_the_last_change_first() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut context = Context::new();
+
+        for name in &["a", "b", "c"] {
+            context.register_change(Box::new(RecordedChange(name, log.clone())));
+        }
+        context.restore_changes().unwrap();
+
+        // the restored changes are not restored again.
+        context.restore_changes().unwrap();
+
+        assert_eq!(*log.lock().unwrap(), vec!["undo c", "undo b", "undo a"]);
+    }
+
+    #[test]
+    fn hands_out_increasing_keys() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut context = Context::new();
+
+        let first = context.register_change(Box::new(RecordedChange("a", log.clone())));
+        let second = context.register_change(Box::new(RecordedChange("b", log.clone())));
+        context.undo_state(second).unwrap();
+        // the key of an undone change is not handed out again.
+        let third = context.register_change(Box::new(RecordedChange("c", log.clone())));
+
+        context.restore_changes().unwrap();
+
+        assert!(first < second && second < third);
+        assert_eq!(*log.lock().unwrap(), vec!["undo b", "undo c", "undo a"]);
     }
 }
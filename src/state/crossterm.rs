@@ -0,0 +1,168 @@
+//! This module contains the terminal state that is shared by the whole process.
+//!
+//! The terminal is one for the whole process, so the changes crossterm makes to it are registered in one shared `Context`.
+//! Besides that the modes that are active are tracked here, so they are not enabled twice and can be queried.
+
+use shared::error::{Error, ErrorKind};
+use Context;
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// The context that is shared by all `Crossterm` handles.
+static SHARED_CONTEXT: OnceLock<Context> = OnceLock::new();
+
+/// Whether raw mode is enabled.
+static RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether an `AlternateScreen` on the terminal switched it to the alternate screen.
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Whether mouse capture is enabled.
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the cursor is shown, it is shown until crossterm hides it.
+static CURSOR_VISIBLE: AtomicBool = AtomicBool::new(true);
+
+/// Handle to the terminal state that is shared by the whole process, it can be cloned and send to other threads.
+///
+/// The changes that crossterm makes on its own, like the noncanonical mode when the cursor position is read and the switch to the alternate screen,
+/// are registered in the shared context, so they are restored together with the changes that are registered in it through this handle.
+///
+/// #Example
+///
+/// ```rust,no_run
+/// extern crate crossterm;
+/// use self::crossterm::terminal::raw::IntoRawMode;
+/// use self::crossterm::{Crossterm, RestoreGuard};
+/// use std::io::stdout;
+///
+/// let crossterm = Crossterm::new();
+/// let mut context = crossterm.context();
+/// let _guard = RestoreGuard::new(&context).unwrap();
+///
+/// let _raw = stdout().into_raw_mode(&mut context).unwrap();
+///
+/// // raw mode is already enabled, so it can not be enabled again through any other handle.
+/// assert!(crossterm.is_raw_mode_enabled());
+/// assert!(stdout().into_raw_mode(&mut Crossterm::new().context()).is_err());
+/// ```
+#[derive(Clone)]
+pub struct Crossterm {
+    context: Context,
+}
+
+impl Crossterm {
+    /// Get an handle to the terminal state that is shared by the whole process.
+    pub fn new() -> Crossterm {
+        Crossterm {
+            context: shared_context().clone(),
+        }
+    }
+
+    /// Get the shared context, the changes that are registered in it are shared with every other handle.
+    pub fn context(&self) -> Context {
+        self.context.clone()
+    }
+
+    /// Restore all changes that are registered in the shared context, the last registered change is restored first.
+    pub fn restore(&self) -> io::Result<()> {
+        self.context.clone().restore_changes()
+    }
+
+    /// Check whether raw mode is enabled, through any context.
+    pub fn is_raw_mode_enabled(&self) -> bool {
+        RAW_MODE.load(Ordering::SeqCst)
+    }
+
+    /// Check whether the terminal is at the alternate screen.
+    ///
+    /// This is the case from when an `AlternateScreen` on the terminal is created until it is dropped,
+    /// an `ToAlternateScreen` that is written yourself or an `AlternateScreen` on another output is not tracked.
+    pub fn is_alternate_screen_active(&self) -> bool {
+        alternate_screen_active()
+    }
+
+    /// Check whether mouse capture is enabled, through any context.
+    pub fn is_mouse_capture_enabled(&self) -> bool {
+        MOUSE_CAPTURE.load(Ordering::SeqCst)
+    }
+
+    /// Check whether the cursor is shown, it is hidden after it is hidden with crossterm until it is shown again.
+    ///
+    /// Only hiding the cursor of the terminal is tracked, hiding it on another output, like an `Vec<u8>`, does not change this.
+    ///
+    /// #Example
+    ///
+    /// ```rust,no_run
+    /// extern crate crossterm;
+    /// use self::crossterm::cursor::cursor;
+    /// use self::crossterm::Crossterm;
+    ///
+    /// let crossterm = Crossterm::new();
+    ///
+    /// cursor().hide().unwrap();
+    /// assert!(!crossterm.is_cursor_visible());
+    ///
+    /// cursor().show().unwrap();
+    /// assert!(crossterm.is_cursor_visible());
+    /// ```
+    pub fn is_cursor_visible(&self) -> bool {
+        CURSOR_VISIBLE.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for Crossterm {
+    fn default() -> Crossterm {
+        Crossterm::new()
+    }
+}
+
+/// Get the context that is shared by the whole process.
+pub(crate) fn shared_context() -> &'static Context {
+    SHARED_CONTEXT.get_or_init(Context::new)
+}
+
+/// Mark raw mode as enabled, an error is returned when it already is.
+pub(crate) fn claim_raw_mode() -> io::Result<()> {
+    claim(&RAW_MODE, "raw mode is already enabled")
+}
+
+/// Remember whether raw mode is enabled.
+pub(crate) fn set_raw_mode(enabled: bool) {
+    RAW_MODE.store(enabled, Ordering::SeqCst);
+}
+
+/// Mark the alternate screen as active, an error is returned when it already is because alternate screens can not be nested.
+pub(crate) fn claim_alternate_screen() -> io::Result<()> {
+    claim(&ALTERNATE_SCREEN, "the alternate screen is already active")
+}
+
+/// Remember whether the alternate screen is active.
+pub(crate) fn set_alternate_screen(active: bool) {
+    ALTERNATE_SCREEN.store(active, Ordering::SeqCst);
+}
+
+/// Check whether an `AlternateScreen` switched the terminal to the alternate screen.
+pub(crate) fn alternate_screen_active() -> bool {
+    ALTERNATE_SCREEN.load(Ordering::SeqCst)
+}
+
+/// Remember whether mouse capture is enabled.
+pub(crate) fn set_mouse_capture(enabled: bool) {
+    MOUSE_CAPTURE.store(enabled, Ordering::SeqCst);
+}
+
+/// Remember whether the cursor is shown.
+pub(crate) fn set_cursor_visible(visible: bool) {
+    CURSOR_VISIBLE.store(visible, Ordering::SeqCst);
+}
+
+/// Set the given mode when it is not set yet, so two threads can not both enable it.
+fn claim(mode: &AtomicBool, message: &str) -> io::Result<()> {
+    match mode.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(Error::new(ErrorKind::AlreadyEnabled, message).into()),
+    }
+}
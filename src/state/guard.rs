@@ -0,0 +1,159 @@
+//! This module contains the `RestoreGuard` that restores the terminal when the program panics or is terminated by an signal.
+//!
+//! Without it an panic leaves the terminal in raw mode at the alternate screen, and the panic message is printed there where nobody can read it.
+//! The guard restores the changes of its `Context` before the panic message is printed, and before the process ends on `SIGINT`, `SIGTERM` or `SIGHUP`.
+
+#[cfg(unix)]
+use kernel::unix_kernel::signal;
+use shared::functions;
+use state;
+use terminal::screen::ToMainScreen;
+use Context;
+
+use std::io::{self, Write};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+
+/// The contexts of the guards that are alive, with the id of their guard.
+static GUARDED: Mutex<Vec<(usize, Context)>> = Mutex::new(Vec::new());
+
+static NEXT_GUARD_ID: AtomicUsize = AtomicUsize::new(0);
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// The signals that terminate the process, the terminal is restored before the process ends by one of them.
+#[cfg(unix)]
+const TERMINATING_SIGNALS: [::libc::c_int; 3] = [::libc::SIGINT, ::libc::SIGTERM, ::libc::SIGHUP];
+
+/// Guard that restores the changes of an `Context` when the program panics or is terminated by an signal, and when it is dropped.
+///
+/// Restoring means that all changes of the context and of the context shared by the whole process are undone, like raw mode and mouse mode,
+/// the terminal switches back to the main screen when it is at the alternate screen, the cursor is shown and the colors are reset.
+/// This happens before the panic message is printed, on unix systems the signal is raised again afterwards so the process still ends the way it would have.
+/// The signals the process ignores, like `SIGHUP` when it is started by `nohup`, stay ignored.
+///
+/// The panic hook that was set before the first guard was created is still called, after the terminal is restored.
+///
+/// #Example
+///
+/// ```rust,no_run
+/// extern crate crossterm;
+/// use self::crossterm::terminal::raw::IntoRawMode;
+/// use self::crossterm::{Context, RestoreGuard};
+/// use std::io::stdout;
+///
+/// let mut context = Context::new();
+/// let _guard = RestoreGuard::new(&context).unwrap();
+///
+/// let _raw = stdout().into_raw_mode(&mut context).unwrap();
+///
+/// // raw mode is disabled before the panic message is printed.
+/// panic!("something went wrong");
+/// ```
+pub struct RestoreGuard {
+    id: usize,
+    context: Context,
+    #[cfg(unix)]
+    signal_ids: Vec<usize>,
+}
+
+impl RestoreGuard {
+    /// Create an guard for the given context, the changes that are registered through any clone of the context are restored by it.
+    ///
+    /// An error is returned when the signal handlers could not be installed.
+    pub fn new(context: &Context) -> io::Result<RestoreGuard> {
+        INSTALL_PANIC_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                restore_all();
+                previous(info);
+            }));
+        });
+
+        let id = NEXT_GUARD_ID.fetch_add(1, Ordering::SeqCst);
+        guarded().push((id, context.clone()));
+
+        #[cfg_attr(not(unix), allow(unused_mut))]
+        let mut guard = RestoreGuard {
+            id,
+            context: context.clone(),
+            #[cfg(unix)]
+            signal_ids: Vec::new(),
+        };
+
+        #[cfg(unix)]
+        {
+            for &signal in TERMINATING_SIGNALS.iter() {
+                // an ignored signal does not end the process, like `SIGHUP` when it is started by `nohup`.
+                if signal::is_ignored(signal) {
+                    continue;
+                }
+
+                // when this fails the guard is dropped, which unregisters the handlers that are already registered.
+                let signal_id = signal::register(signal, move || {
+                    restore_all();
+                    signal::reraise(signal);
+                })?;
+
+                guard.signal_ids.push(signal_id);
+            }
+        }
+
+        Ok(guard)
+    }
+}
+
+impl Drop for RestoreGuard {
+    fn drop(&mut self) {
+        guarded().retain(|&(id, _)| id != self.id);
+
+        #[cfg(unix)]
+        {
+            for &signal_id in &self.signal_ids {
+                signal::unregister(signal_id);
+            }
+        }
+
+        // there is no way to report an error from here, the terminal is restored as far as possible.
+        let _ = self.context.restore_changes();
+    }
+}
+
+/// Lock the guarded contexts, they are only locked shortly so an panic while they are locked leaves them usable.
+fn guarded() -> ::std::sync::MutexGuard<'static, Vec<(usize, Context)>> {
+    GUARDED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Restore the contexts of all guards, the shared context and the parts of the terminal that are not registered in an context.
+fn restore_all() {
+    let contexts: Vec<Context> = guarded()
+        .iter()
+        .map(|(_, context)| context.clone())
+        .collect();
+
+    if contexts.is_empty() {
+        return;
+    }
+
+    // the changes crossterm makes on its own are registered in the shared context.
+    for context in contexts.iter().chain(Some(state::shared_context())) {
+        let _ = context.try_restore_changes();
+    }
+
+    // the escape codes would end up in the output when it is redirected to an file.
+    if !functions::is_stdout_tty() {
+        return;
+    }
+
+    let mut stdout = io::stdout();
+
+    if state::alternate_screen_active() {
+        let _ = write!(stdout, "{}", ToMainScreen);
+        state::set_alternate_screen(false);
+    }
+
+    let _ = stdout.write_all(concat!(csi!("0m"), csi!("?25h")).as_bytes());
+    state::set_cursor_visible(true);
+    let _ = stdout.flush();
+}
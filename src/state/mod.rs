@@ -5,5 +5,14 @@
 
 pub mod commands;
 mod context;
+mod crossterm;
+mod guard;
+#[cfg(unix)]
+mod suspend;
 
-pub use self::context::Context;
+pub(crate) use self::crossterm::*;
+pub use self::context::{ChangeKind, Context, StateKey};
+pub use self::crossterm::Crossterm;
+pub use self::guard::RestoreGuard;
+#[cfg(unix)]
+pub use self::suspend::SuspendHandler;
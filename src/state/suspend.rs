@@ -0,0 +1,160 @@
+//! This module contains the `SuspendHandler` that takes care of the terminal when the process is suspended with ctrl + z.
+//!
+//! The terminal sends `SIGTSTP` to the process on ctrl + z, the process is stopped until the shell continues it with `SIGCONT`.
+//! Without handling it the shell is left in raw mode at the alternate screen of the stopped process.
+//! In raw mode the terminal does not send the signal, ctrl + z is read as an key instead and the program suspends itself with `SuspendHandler::suspend()`.
+
+use kernel::unix_kernel::signal;
+use libc::SIGTSTP;
+use shared::functions;
+use state::{self, Crossterm};
+use Context;
+
+use std::io::{self, Write};
+
+/// Handler that undoes the changes of an `Context` when the process is suspended and applies them again when it is continued,
+/// the way vim and less behave on ctrl + z.
+///
+/// When `SIGTSTP` is received the changes of the context and of the context shared by the whole process are undone,
+/// the last registered change first, and the cursor is shown. Then the process is stopped.
+/// When the process is continued the changes are applied again in the order they are registered and the cursor is hidden again when it was hidden.
+///
+/// The content of the alternate screen is gone after the process is continued, `TerminalInput::read()` reports an `Event::Redraw`
+/// after the changes are applied again, so the program knows it should draw everything again.
+///
+/// The process is suspended the normal way again after the handler is dropped.
+///
+/// The terminal only sends `SIGTSTP` when it is not in raw mode. In raw mode ctrl + z is read as `KeyEvent::Ctrl('z')`,
+/// the program should then call `suspend()` to suspend the process the same way.
+///
+/// #Example
+///
+/// ```rust,no_run
+/// extern crate crossterm;
+/// use self::crossterm::input::{input, Event, KeyEvent};
+/// use self::crossterm::terminal::raw::IntoRawMode;
+/// use self::crossterm::terminal::screen::AlternateScreen;
+/// use self::crossterm::{Context, SuspendHandler};
+/// use std::io::stdout;
+///
+/// let mut context = Context::new();
+/// let handler = SuspendHandler::new(&context).unwrap();
+///
+/// let _screen = AlternateScreen::from(stdout()).unwrap();
+/// let _raw = stdout().into_raw_mode(&mut context).unwrap();
+///
+/// // the same input is read the whole time, so the `Event::Redraw` after the process is continued is not missed.
+/// let mut input = input();
+///
+/// loop {
+///     match input.read() {
+///         // raw mode is enabled, so the terminal does not suspend the process on ctrl + z.
+///         Ok(Event::Key(KeyEvent::Ctrl('z'))) => handler.suspend(),
+///         Ok(Event::Redraw) => { /* draw everything again */ }
+///         Ok(Event::Key(KeyEvent::Char('q'))) => break,
+///         Ok(_) => {}
+///         Err(e) => panic!("could not read the input: {}", e),
+///     }
+/// }
+/// ```
+pub struct SuspendHandler {
+    id: usize,
+    context: Context,
+}
+
+impl SuspendHandler {
+    /// Create an handler for the given context, the changes that are registered through any clone of the context are undone while the process is suspended.
+    ///
+    /// An error is returned when the signal handler could not be installed.
+    pub fn new(context: &Context) -> io::Result<SuspendHandler> {
+        let handler_context = context.clone();
+        let id = signal::register(SIGTSTP, move || suspend(&handler_context, stop))?;
+
+        Ok(SuspendHandler {
+            id,
+            context: context.clone(),
+        })
+    }
+
+    /// Suspend the process like the terminal does on ctrl + z, this returns when the process is continued.
+    ///
+    /// This is meant for raw mode, where ctrl + z is read as `KeyEvent::Ctrl('z')` instead of suspending the process.
+    /// The changes are undone and applied again the same way as when `SIGTSTP` is received.
+    pub fn suspend(&self) {
+        suspend(&self.context, stop);
+    }
+}
+
+impl Drop for SuspendHandler {
+    fn drop(&mut self) {
+        signal::unregister(self.id);
+    }
+}
+
+/// Undo the changes of the given and the shared context, stop the process and apply the changes again when the process is continued.
+fn suspend<F: FnOnce()>(context: &Context, stop: F) {
+    let shared = state::shared_context();
+    // the shared context is only suspended once when it is the given context.
+    let contexts: Vec<&Context> = if context.shares_changes_with(shared) {
+        vec![context]
+    } else {
+        vec![shared, context]
+    };
+
+    // the changes are applied as far as possible, there is no way to report an error from the signal thread.
+    for context in contexts.iter().rev() {
+        let _ = context.suspend_changes();
+    }
+
+    let cursor_hidden = !Crossterm::new().is_cursor_visible();
+    if cursor_hidden {
+        write_to_terminal(csi!("?25h"));
+    }
+
+    stop();
+
+    // the process is continued.
+    for context in &contexts {
+        let _ = context.resume_changes();
+    }
+
+    if cursor_hidden {
+        write_to_terminal(csi!("?25l"));
+    }
+}
+
+/// Stop the process until it is continued.
+fn stop() {
+    signal::raise_default(SIGTSTP);
+}
+
+/// Write the given escape code to stdout when it is a terminal.
+fn write_to_terminal(code: &str) {
+    if functions::is_stdout_tty() {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(code.as_bytes());
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suspend;
+    use state::context::tests::RecordedChange;
+    use state::Context;
+
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn undoes_the_changes_while_stopped() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut context = Context::new();
+        context.register_change(Box::new(RecordedChange("a", log.clone())));
+
+        suspend(&context, || log.lock().unwrap().push("stop".to_string()));
+
+        assert_eq!(*log.lock().unwrap(), vec!["undo a", "stop", "execute a"]);
+        // the change is applied again, so it is still registered.
+        assert_eq!(context.registrations().len(), 1);
+    }
+}
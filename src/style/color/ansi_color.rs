@@ -1,7 +1,8 @@
 //! This is an ANSI specific implementation for styling related action.
 //! This module is used for windows 10 terminals and unix terminals by default.
 
-use super::super::{Color, ColorType};
+use super::super::{Color, ColorType, UnderlineStyle};
+use super::support::{color_support, styled_underline_support, ColorSupport};
 use super::ITerminalColor;
 use Construct;
 
@@ -18,69 +19,238 @@ impl Construct for AnsiColor {
 }
 
 impl ITerminalColor for AnsiColor {
-    fn set_fg(&self, fg_color: Color) {
-        let mut some_writer = io::stdout();
+    fn set_fg(&self, output: &mut dyn Write, fg_color: Color) -> io::Result<()> {
+        self.set_fg_for(output, fg_color, color_support())
+    }
+
+    fn set_bg(&self, output: &mut dyn Write, bg_color: Color) -> io::Result<()> {
+        self.set_bg_for(output, bg_color, color_support())
+    }
+
+    fn reset(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, csi!("0m"))
+    }
+
+    fn color_value(&self, color: Color, color_type: ColorType) -> String {
+        color_code(color, color_type, color_support())
+    }
+
+    fn set_fg_for(
+        &self,
+        output: &mut dyn Write,
+        fg_color: Color,
+        support: ColorSupport,
+    ) -> io::Result<()> {
         write!(
-            &mut some_writer,
+            output,
             csi!("{}m"),
-            self.color_value(fg_color, ColorType::Foreground)
-        );
+            color_code(fg_color, ColorType::Foreground, support)
+        )
     }
 
-    fn set_bg(&self, bg_color: Color) {
-        let mut some_writer = io::stdout();
+    fn set_bg_for(
+        &self,
+        output: &mut dyn Write,
+        bg_color: Color,
+        support: ColorSupport,
+    ) -> io::Result<()> {
         write!(
-            &mut some_writer,
+            output,
             csi!("{}m"),
-            self.color_value(bg_color, ColorType::Background)
-        );
+            color_code(bg_color, ColorType::Background, support)
+        )
+    }
+}
+
+/// Get the parameters of the `m` control sequence that sets the given color with the given color support.
+///
+/// The colors the terminal can not show are converted to the nearest color it can show.
+/// The terminals with only 16 colors may not know the `38;5;n` codes, so they get the basic codes like `31` and `91`.
+pub(crate) fn color_code(color: Color, color_type: ColorType, support: ColorSupport) -> String {
+    // the underline color is separated with colons, the terminals that do not know it ignore it as a whole.
+    let (prefix, separator) = match color_type {
+        ColorType::Foreground => ("38", ";"),
+        ColorType::Background => ("48", ";"),
+        ColorType::Underline => ("58", ":"),
+    };
+
+    let index = match support.downsample(color) {
+        Color::Black => 0,
+        Color::Red => 9,
+        Color::DarkRed => 1,
+        Color::Green => 10,
+        Color::DarkGreen => 2,
+        Color::Yellow => 11,
+        Color::DarkYellow => 3,
+        Color::Blue => 12,
+        Color::DarkBlue => 4,
+        Color::Magenta => 13,
+        Color::DarkMagenta => 5,
+        Color::Cyan => 14,
+        Color::DarkCyan => 6,
+        Color::Grey => 15,
+        Color::White => 7,
+        Color::Rgb { r, g, b } => {
+            // the colon notation has an (empty) color space id before the components.
+            let color_space = if separator == ":" { ":" } else { "" };
+            return format!(
+                "{prefix}{sep}2{sep}{space}{r}{sep}{g}{sep}{b}",
+                prefix = prefix,
+                sep = separator,
+                space = color_space,
+                r = r,
+                g = g,
+                b = b
+            );
+        }
+        Color::AnsiValue(val) => val,
+    };
+
+    match (support, color_type) {
+        (ColorSupport::Ansi16, ColorType::Foreground) if index < 8 => (30 + index).to_string(),
+        (ColorSupport::Ansi16, ColorType::Foreground) if index < 16 => (90 + index - 8).to_string(),
+        (ColorSupport::Ansi16, ColorType::Background) if index < 8 => (40 + index).to_string(),
+        (ColorSupport::Ansi16, ColorType::Background) if index < 16 => {
+            (100 + index - 8).to_string()
+        }
+        _ => format!("{}{}5{}{}", prefix, separator, separator, index),
     }
+}
+
+impl AnsiColor {
+    /// Get the escape codes that underline the text with the given style.
+    ///
+    /// An single underline is always written first, so the text is still underlined when the terminal
+    /// ignores the underline style. The style is only written when the terminal supports it.
+    pub fn underline_style_codes(&self, style: UnderlineStyle) -> String {
+        if style == UnderlineStyle::Single || !styled_underline_support() {
+            return csi!("4m").to_string();
+        }
 
-    fn reset(&self) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("0m"));
+        format!(concat!(csi!("4m"), csi!("4:{}m")), style as u8)
     }
 
-    fn color_value(&self, color: Color, color_type: ColorType) -> String {
-        let mut ansi_value = String::new();
+    /// Get the escape code that sets the underline color, empty when the terminal does not support underline colors.
+    pub fn underline_color_codes(&self, color: Color) -> String {
+        self.underline_color_codes_for(color, color_support())
+    }
 
-        match color_type {
-            ColorType::Foreground => ansi_value.push_str("38;"),
-            ColorType::Background => ansi_value.push_str("48;"),
+    /// Get the escape code that sets the underline color for an output with the given color support.
+    pub(crate) fn underline_color_codes_for(&self, color: Color, support: ColorSupport) -> String {
+        if !styled_underline_support() {
+            return String::new();
         }
 
-        #[cfg(unix)]
-        let rgb_val: String;
-
-        let color_val = match color {
-            Color::Black => "5;0",
-            Color::Red => "5;9",
-            Color::DarkRed => "5;1",
-            Color::Green => "5;10",
-            Color::DarkGreen => "5;2",
-            Color::Yellow => "5;11",
-            Color::DarkYellow => "5;3",
-            Color::Blue => "5;12",
-            Color::DarkBlue => "5;4",
-            Color::Magenta => "5;13",
-            Color::DarkMagenta => "5;5",
-            Color::Cyan => "5;14",
-            Color::DarkCyan => "5;6",
-            Color::Grey => "5;15",
-            Color::White => "5;7",
-            #[cfg(unix)]
-            Color::Rgb { r, g, b } => {
-                rgb_val = format!("2;{};{};{}", r, g, b);
-                rgb_val.as_str()
-            }
-            #[cfg(unix)]
-            Color::AnsiValue(val) => {
-                rgb_val = format!("5;{}", val);
-                rgb_val.as_str()
-            }
-        };
-
-        ansi_value.push_str(color_val);
-        ansi_value
+        format!(
+            csi!("{}m"),
+            color_code(color, ColorType::Underline, support)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::color_code;
+    use style::{Color, ColorSupport, ColorType};
+
+    const ORANGE: Color = Color::Rgb {
+        r: 255,
+        g: 135,
+        b: 0,
+    };
+
+    #[test]
+    fn writes_every_color_with_true_color() {
+        let support = ColorSupport::TrueColor;
+
+        assert_eq!(
+            color_code(Color::Red, ColorType::Foreground, support),
+            "38;5;9"
+        );
+        assert_eq!(
+            color_code(Color::AnsiValue(208), ColorType::Background, support),
+            "48;5;208"
+        );
+        assert_eq!(
+            color_code(ORANGE, ColorType::Foreground, support),
+            "38;2;255;135;0"
+        );
+        assert_eq!(
+            color_code(ORANGE, ColorType::Underline, support),
+            "58:2::255:135:0"
+        );
+    }
+
+    #[test]
+    fn writes_the_nearest_palette_color_with_256_colors() {
+        let support = ColorSupport::Ansi256;
+
+        assert_eq!(
+            color_code(Color::DarkBlue, ColorType::Background, support),
+            "48;5;4"
+        );
+        assert_eq!(
+            color_code(ORANGE, ColorType::Foreground, support),
+            "38;5;208"
+        );
+        assert_eq!(
+            color_code(ORANGE, ColorType::Underline, support),
+            "58:5:208"
+        );
+    }
+
+    #[test]
+    fn writes_the_basic_codes_with_16_colors() {
+        let support = ColorSupport::Ansi16;
+
+        assert_eq!(
+            color_code(Color::Black, ColorType::Foreground, support),
+            "30"
+        );
+        assert_eq!(
+            color_code(Color::White, ColorType::Foreground, support),
+            "37"
+        );
+        assert_eq!(color_code(Color::Red, ColorType::Foreground, support), "91");
+        assert_eq!(
+            color_code(Color::Grey, ColorType::Foreground, support),
+            "97"
+        );
+        assert_eq!(
+            color_code(Color::DarkRed, ColorType::Background, support),
+            "41"
+        );
+        assert_eq!(
+            color_code(Color::Cyan, ColorType::Background, support),
+            "106"
+        );
+        assert_eq!(
+            color_code(Color::AnsiValue(8), ColorType::Background, support),
+            "100"
+        );
+        assert_eq!(color_code(ORANGE, ColorType::Foreground, support), "33");
+        assert_eq!(
+            color_code(Color::AnsiValue(196), ColorType::Background, support),
+            "101"
+        );
+        // there is no basic code for the underline color.
+        assert_eq!(
+            color_code(Color::Red, ColorType::Underline, support),
+            "58:5:9"
+        );
+    }
+
+    #[test]
+    fn writes_the_colors_unchanged_without_color_support() {
+        let support = ColorSupport::None;
+
+        assert_eq!(
+            color_code(Color::Red, ColorType::Foreground, support),
+            "38;5;9"
+        );
+        assert_eq!(
+            color_code(ORANGE, ColorType::Background, support),
+            "48;2;255;135;0"
+        );
     }
 }
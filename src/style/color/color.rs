@@ -1,34 +1,137 @@
 //! With this module you can perform actions that are color related.
 //! Like styling the font, foreground color and background.
 
+use super::support::{color_support_of, style_policy, ColorSupport, StylePolicy};
 use super::*;
 use shared::functions;
 use style::{Color, ObjectStyle, StyledObject};
-use {Construct, Context};
+use {Construct, OutputTarget, TerminalOutput};
 
-use std::ops::Drop;
-use std::{fmt, io};
+use std::fmt;
+use std::io::{self, Stdout, Write};
 
 /// Struct that stores an specific platform implementation for color related actions.
-pub struct TerminalColor {
-    terminal_color: Option<Box<ITerminalColor>>,
+///
+/// The color actions are written to the output `W`, which is stdout by default.
+pub struct TerminalColor<W: Write = Stdout> {
+    terminal_color: Option<Box<dyn ITerminalColor>>,
+    output: W,
+    /// The output the color support and style policy are detected for.
+    target: OutputTarget,
+    style_policy: Option<StylePolicy>,
 }
 
-impl TerminalColor {
+impl TerminalColor<Stdout> {
     /// Create new instance whereon color related actions can be performed.
-    pub fn new() -> TerminalColor {
+    pub fn new() -> TerminalColor<Stdout> {
         #[cfg(target_os = "windows")]
         let color =
-            functions::get_module::<Box<ITerminalColor>>(WinApiColor::new(), AnsiColor::new());
+            functions::get_module::<Box<dyn ITerminalColor>>(WinApiColor::new(), AnsiColor::new());
 
         #[cfg(not(target_os = "windows"))]
-        let color = Some(AnsiColor::new() as Box<ITerminalColor>);
+        let color = Some(AnsiColor::new() as Box<dyn ITerminalColor>);
 
         TerminalColor {
             terminal_color: color,
+            output: io::stdout(),
+            target: OutputTarget::stdout(),
+            style_policy: None,
+        }
+    }
+}
+
+impl<W: Write + TerminalOutput> TerminalColor<W> {
+    /// Create new instance that writes the color actions to the given output,
+    /// like stderr, an file or an `Vec<u8>`.
+    ///
+    /// The actions are always written as `ANSI escape codes`, because the `WINAPI` can not write to an arbitrary output.
+    /// The color support and the `StylePolicy::Auto` are detected for the given output.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::{Color, TerminalColor};
+    /// use std::io::{stderr, Write};
+    ///
+    /// // Color the text on stderr, so that the data written to stdout stays plain.
+    /// let mut colored_terminal = TerminalColor::from_output(stderr());
+    ///
+    /// colored_terminal.set_fg(Color::Red).unwrap();
+    /// writeln!(colored_terminal.output_mut(), "Something went wrong").unwrap();
+    /// colored_terminal.reset().unwrap();
+    ///
+    /// ```
+    pub fn from_output(output: W) -> TerminalColor<W> {
+        TerminalColor {
+            terminal_color: Some(AnsiColor::new() as Box<dyn ITerminalColor>),
+            target: OutputTarget::of(&output),
+            output,
+            style_policy: None,
+        }
+    }
+}
+
+impl<W: Write> TerminalColor<W> {
+    /// Create new instance that performs the color actions with the given implementation,
+    /// for example the `VirtualTerminal` in tests.
+    ///
+    /// The color support and the `StylePolicy::Auto` are the ones of stdout.
+    pub fn with_implementation(color: Box<dyn ITerminalColor>, output: W) -> TerminalColor<W> {
+        TerminalColor {
+            terminal_color: Some(color),
+            output,
+            target: OutputTarget::stdout(),
+            style_policy: None,
         }
     }
 
+    /// Get a reference to the output the color actions are written to.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Get a mutable reference to the output the color actions are written to.
+    pub fn output_mut(&mut self) -> &mut W {
+        &mut self.output
+    }
+
+    /// Consume the instance and get the output the color actions are written to.
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Get the style policy of this instance, the global `style_policy()` when it has no policy of its own.
+    pub fn style_policy(&self) -> StylePolicy {
+        self.style_policy.unwrap_or_else(style_policy)
+    }
+
+    /// Set the style policy of this instance, instead of the global `style_policy()`.
+    ///
+    /// When the styling is disabled the color actions write nothing, `StylePolicy::Auto` detects this from the output.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::{Color, StylePolicy, TerminalColor};
+    ///
+    /// let mut colored_terminal = TerminalColor::from_output(Vec::new());
+    ///
+    /// colored_terminal.set_style_policy(StylePolicy::Never);
+    /// colored_terminal.set_fg(Color::Red).unwrap();
+    /// assert!(colored_terminal.output().is_empty());
+    ///
+    /// colored_terminal.set_style_policy(StylePolicy::Always);
+    /// colored_terminal.set_fg(Color::Red).unwrap();
+    /// assert_eq!(colored_terminal.output(), b"\x1B[38;5;9m");
+    /// ```
+    pub fn set_style_policy(&mut self, policy: StylePolicy) {
+        self.style_policy = Some(policy);
+    }
+
     /// Set the foreground color to the given color.
     ///
     /// #Example
@@ -42,14 +145,21 @@ impl TerminalColor {
     /// let mut colored_terminal = color();
     ///
     /// // Set foreground color of the font
-    /// colored_terminal.set_fg(Color::Red);
+    /// colored_terminal.set_fg(Color::Red).unwrap();
     /// // crossterm provides to set the background from &str or String
-    /// colored_terminal.set_fg(Color::from("Red"));
+    /// colored_terminal.set_fg(Color::from("Red")).unwrap();
     ///
     /// ```
-    pub fn set_fg(&mut self, color: Color) {
-        if let Some(ref terminal_color) = self.terminal_color {
-            terminal_color.set_fg(color);
+    pub fn set_fg(&mut self, color: Color) -> io::Result<()> {
+        if !self.is_styling_enabled() {
+            return Ok(());
+        }
+
+        let support = self.color_support();
+
+        match self.terminal_color {
+            Some(ref terminal_color) => terminal_color.set_fg_for(&mut self.output, color, support),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -67,14 +177,21 @@ impl TerminalColor {
     /// let mut colored_terminal = color();
     ///
     /// // Set background color of the font
-    /// colored_terminal.set_bg(Color::Red);
+    /// colored_terminal.set_bg(Color::Red).unwrap();
     /// // crossterm provides to set the background from &str or String
-    /// colored_terminal.set_bg(Color::from("Red"));
+    /// colored_terminal.set_bg(Color::from("Red")).unwrap();
     ///
     /// ```
-    pub fn set_bg(&mut self, color: Color) {
-        if let Some(ref terminal_color) = self.terminal_color {
-            terminal_color.set_bg(color);
+    pub fn set_bg(&mut self, color: Color) -> io::Result<()> {
+        if !self.is_styling_enabled() {
+            return Ok(());
+        }
+
+        let support = self.color_support();
+
+        match self.terminal_color {
+            Some(ref terminal_color) => terminal_color.set_bg_for(&mut self.output, color, support),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -89,30 +206,46 @@ impl TerminalColor {
     /// // Get colored terminal instance
     /// let mut colored_terminal = color();
     ///
-    /// colored_terminal.reset();
+    /// colored_terminal.reset().unwrap();
     ///
     /// ```
-    pub fn reset(&mut self) {
-        if let Some(ref terminal_color) = self.terminal_color {
-            terminal_color.reset();
+    pub fn reset(&mut self) -> io::Result<()> {
+        if !self.is_styling_enabled() {
+            return Ok(());
+        }
+
+        match self.terminal_color {
+            Some(ref terminal_color) => terminal_color.reset(&mut self.output),
+            None => Err(functions::not_supported()),
         }
     }
 
-    /// Get available color count.
+    /// Get the color support of the output, the colors are converted to the nearest color it can show.
+    pub fn color_support(&self) -> ColorSupport {
+        color_support_of(self.target)
+    }
+
+    /// Get available color count, this is 0 when the output is no terminal or colors are disabled with `NO_COLOR`.
+    ///
+    /// Terminals that support true color also report 256 colors, use `color_support()` to check for true color.
     pub fn get_available_color_count(&self) -> io::Result<u16> {
-        use std::env;
-
-        Ok(match env::var_os("TERM") {
-            Some(val) => {
-                if val.to_str().unwrap_or("").contains("256color") {
-                    256
-                } else {
-                    8
-                }
-            }
-            None => 8,
+        Ok(match self.color_support() {
+            ColorSupport::None => 0,
+            ColorSupport::Ansi16 => 16,
+            ColorSupport::Ansi256 | ColorSupport::TrueColor => 256,
         })
     }
+
+    /// Check if the styling is written to the output with the style policy and the color support of the output.
+    fn is_styling_enabled(&self) -> bool {
+        self.style_policy().is_enabled_for(self.color_support())
+    }
+}
+
+impl Default for TerminalColor<Stdout> {
+    fn default() -> TerminalColor<Stdout> {
+        TerminalColor::new()
+    }
 }
 
 /// Get an TerminalColor implementation whereon color related actions can be performed.
@@ -128,9 +261,9 @@ impl TerminalColor {
 /// let mut colored_terminal = color();
 ///
 /// // preform some actions on the colored terminal
-/// colored_terminal.set_fg(Color::Red);
-/// colored_terminal.set_bg(Color::Blue);
-/// colored_terminal.reset();
+/// colored_terminal.set_fg(Color::Red).unwrap();
+/// colored_terminal.set_bg(Color::Blue).unwrap();
+/// colored_terminal.reset().unwrap();
 /// ```
 pub fn color() -> Box<TerminalColor> {
     Box::from(TerminalColor::new())
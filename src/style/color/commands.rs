@@ -0,0 +1,138 @@
+//! This module contains the styling commands that can be used with the `queue!` and `execute!` macros.
+
+use super::super::{Attribute, Color, UnderlineStyle};
+use super::support::color_support_of;
+use super::AnsiColor;
+use super::ITerminalColor;
+#[cfg(target_os = "windows")]
+use super::WinApiColor;
+#[cfg(windows)]
+use Construct;
+use {OutputTarget, QueueableCommand};
+
+use std::io::{self, Write};
+
+/// Command that sets the foreground color.
+///
+/// The color is converted to the nearest color the output can show, see `TerminalOutput`.
+///
+/// #Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{Color, ResetColor, SetFg};
+/// use self::crossterm::Print;
+///
+/// fn main() {
+///     let mut buffer = Vec::new();
+///     queue!(buffer, SetFg(Color::DarkRed), Print("error"), ResetColor).unwrap();
+///
+///     assert_eq!(buffer, b"\x1B[38;5;1merror\x1B[0m");
+/// }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct SetFg(pub Color);
+
+impl QueueableCommand for SetFg {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiColor.set_fg(output, self.0)
+    }
+
+    fn write_ansi_to(&self, output: &mut dyn Write, target: OutputTarget) -> io::Result<()> {
+        AnsiColor.set_fg_for(output, self.0, color_support_of(target))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiColor::new().set_fg(output, self.0)
+    }
+}
+
+/// Command that sets the background color.
+#[derive(Debug, Copy, Clone)]
+pub struct SetBg(pub Color);
+
+impl QueueableCommand for SetBg {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiColor.set_bg(output, self.0)
+    }
+
+    fn write_ansi_to(&self, output: &mut dyn Write, target: OutputTarget) -> io::Result<()> {
+        AnsiColor.set_bg_for(output, self.0, color_support_of(target))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiColor::new().set_bg(output, self.0)
+    }
+}
+
+/// Command that applies the given attribute to the text that is written after it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SetAttr(pub Attribute);
+
+impl QueueableCommand for SetAttr {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, csi!("{}m"), self.0 as i16)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, _output: &mut dyn Write) -> io::Result<()> {
+        // the windows console has no text attributes, so the text is written without them.
+        Ok(())
+    }
+}
+
+/// Command that underlines the text that is written after it with the given style.
+///
+/// The text gets an single underline when the terminal does not support underline styles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SetUnderlineStyle(pub UnderlineStyle);
+
+impl QueueableCommand for SetUnderlineStyle {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        output.write_all(AnsiColor.underline_style_codes(self.0).as_bytes())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, _output: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Command that sets the color of the underline, nothing is written when the terminal does not support underline colors.
+#[derive(Debug, Copy, Clone)]
+pub struct SetUnderlineColor(pub Color);
+
+impl QueueableCommand for SetUnderlineColor {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        output.write_all(AnsiColor.underline_color_codes(self.0).as_bytes())
+    }
+
+    fn write_ansi_to(&self, output: &mut dyn Write, target: OutputTarget) -> io::Result<()> {
+        let codes = AnsiColor.underline_color_codes_for(self.0, color_support_of(target));
+        output.write_all(codes.as_bytes())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, _output: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Command that resets the colors and attributes to the terminal default.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ResetColor;
+
+impl QueueableCommand for ResetColor {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiColor.reset(output)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiColor::new().reset(output)
+    }
+}
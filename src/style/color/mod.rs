@@ -1,29 +1,61 @@
+#[allow(clippy::module_inception)]
 pub mod color;
 
 mod ansi_color;
+pub mod commands;
+pub mod support;
 #[cfg(target_os = "windows")]
 mod winapi_color;
 
-use self::ansi_color::AnsiColor;
+pub use self::ansi_color::AnsiColor;
+use self::support::ColorSupport;
 #[cfg(target_os = "windows")]
 use self::winapi_color::WinApiColor;
 use super::{Color, ColorType};
 
-///! This trait defines the actions that can be preformed with the terminal color.
-///! This trait can be implemented so that an concrete implementation of the ITerminalColor can forfill
-///! the wishes to work on an specific platform.
-///!
-///! ## For example:
-///!
-///! This trait is implemented for `WINAPI` (Windows specific) and `ANSI` (Unix specific),
-///! so that color related actions can be preformed on both unix and windows systems.
+use std::io::{self, Write};
+
+/// This trait defines the actions that can be preformed with the terminal color.
+/// This trait can be implemented so that an concrete implementation of the ITerminalColor can forfill
+/// the wishes to work on an specific platform.
+///
+/// ## For example:
+///
+/// This trait is implemented for `WINAPI` (Windows specific) and `ANSI` (Unix specific),
+/// so that color related actions can be preformed on both unix and windows systems.
+///
+/// The actions are written to the given output, implementations that do not write to the output flush it first
+/// so that everything that is written before the action keeps its colors.
 pub trait ITerminalColor {
     /// Set the foreground color to the given color.
-    fn set_fg(&self, fg_color: Color);
+    fn set_fg(&self, output: &mut dyn Write, fg_color: Color) -> io::Result<()>;
     /// Set the background color to the given color.
-    fn set_bg(&self, fg_color: Color);
+    fn set_bg(&self, output: &mut dyn Write, fg_color: Color) -> io::Result<()>;
     /// Reset the terminal color to default.
-    fn reset(&self);
+    fn reset(&self, output: &mut dyn Write) -> io::Result<()>;
     /// Gets an value that represents an color from the given `Color` and `ColorType`.
     fn color_value(&self, color: Color, color_type: ColorType) -> String;
+
+    /// Set the foreground color to the given color, for an output with the given color support.
+    ///
+    /// The implementations that write `ANSI escape codes` convert the color to the color support,
+    /// the others set the color the same as `set_fg()`.
+    fn set_fg_for(
+        &self,
+        output: &mut dyn Write,
+        fg_color: Color,
+        _support: ColorSupport,
+    ) -> io::Result<()> {
+        self.set_fg(output, fg_color)
+    }
+
+    /// Set the background color to the given color, for an output with the given color support.
+    fn set_bg_for(
+        &self,
+        output: &mut dyn Write,
+        bg_color: Color,
+        _support: ColorSupport,
+    ) -> io::Result<()> {
+        self.set_bg(output, bg_color)
+    }
 }
@@ -0,0 +1,372 @@
+//! This module detects how many colors the terminal supports and converts the colors the terminal can not show
+//! to the nearest color it can show.
+//!
+//! The color support is detected from the environment:
+//! - `NO_COLOR` disables colors.
+//! - Colors are disabled when the output is no terminal, unless `CLICOLOR_FORCE` is set to something else than `0`.
+//! - `COLORTERM=truecolor` or `COLORTERM=24bit` enables true color.
+//! - `TERM` containing `256color` enables the 256 color palette, `TERM=dumb` disables colors.
+//!
+//! The underline styles and underline colors are only written when the terminal is known to support them,
+//! like kitty, WezTerm, foot, Ghostty, Alacritty and the terminals based on VTE.
+//!
+//! Whether the colors and attributes are written at all is decided by the `StylePolicy`,
+//! which by default writes them only when there is color support.
+//!
+//! The global color support is the one of stdout, the color support of the other outputs is detected for them,
+//! see `TerminalOutput`.
+
+use super::super::Color;
+use shared::functions;
+use OutputTarget;
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The colors an terminal can show.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSupport {
+    /// The terminal shows no colors, or the output is no terminal.
+    None,
+    /// The 16 named colors.
+    Ansi16,
+    /// The 256 colors of the ANSI palette.
+    Ansi256,
+    /// All 24 bit RGB colors.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detect the color support of stdout from the environment of the process.
+    pub fn detect() -> ColorSupport {
+        ColorSupport::from_env(|name| env::var(name).ok(), functions::is_stdout_tty())
+    }
+
+    /// Detect the color support from the given environment variables and whether the output is an terminal.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::ColorSupport;
+    ///
+    /// let env = |name: &str| match name {
+    ///     "TERM" => Some("xterm-256color".to_string()),
+    ///     _ => None,
+    /// };
+    ///
+    /// assert_eq!(ColorSupport::from_env(env, true), ColorSupport::Ansi256);
+    /// // when the output is redirected to an file no colors are written.
+    /// assert_eq!(ColorSupport::from_env(env, false), ColorSupport::None);
+    /// ```
+    pub fn from_env<F>(var: F, is_tty: bool) -> ColorSupport
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorSupport::None;
+        }
+
+        let forced = var("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+
+        if !is_tty && !forced {
+            return ColorSupport::None;
+        }
+
+        let term = var("TERM").unwrap_or_default().to_lowercase();
+        let colorterm = var("COLORTERM").unwrap_or_default().to_lowercase();
+
+        if colorterm == "truecolor"
+            || colorterm == "24bit"
+            || term.contains("truecolor")
+            || term.contains("24bit")
+            || term.contains("direct")
+        {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else if term == "dumb" && !forced {
+            ColorSupport::None
+        } else if term.is_empty() && cfg!(windows) {
+            // the windows consoles that support `ANSI escape codes` also support true color.
+            ColorSupport::TrueColor
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Convert the given color to the nearest color this color support can show.
+    ///
+    /// The named colors are never changed, when there is no color support at all the color is not changed either
+    /// because it is not known where the output ends up.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::{Color, ColorSupport};
+    ///
+    /// let orange = Color::Rgb { r: 255, g: 135, b: 0 };
+    ///
+    /// assert_eq!(ColorSupport::TrueColor.downsample(orange), orange);
+    /// assert_eq!(ColorSupport::Ansi256.downsample(orange), Color::AnsiValue(208));
+    /// assert_eq!(ColorSupport::Ansi16.downsample(orange), Color::DarkYellow);
+    /// ```
+    pub fn downsample(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorSupport::Ansi256, Color::Rgb { r, g, b }) => {
+                Color::from_ansi_index(nearest(16..=255, (r, g, b)))
+            }
+            (ColorSupport::Ansi16, Color::Rgb { r, g, b }) => {
+                Color::from_ansi_index(nearest(0..=15, (r, g, b)))
+            }
+            (ColorSupport::Ansi16, Color::AnsiValue(index)) if index > 15 => {
+                Color::from_ansi_index(nearest(0..=15, palette_rgb(index)))
+            }
+            _ => color,
+        }
+    }
+
+    /// Get the number of colors this color support can show, true color is reported as `u32::MAX`.
+    pub fn color_count(self) -> u32 {
+        match self {
+            ColorSupport::None => 0,
+            ColorSupport::Ansi16 => 16,
+            ColorSupport::Ansi256 => 256,
+            ColorSupport::TrueColor => u32::MAX,
+        }
+    }
+}
+
+/// The detected color support, `0` when it is not detected yet, otherwise the index of the `ColorSupport` plus 1.
+static COLOR_SUPPORT: AtomicU8 = AtomicU8::new(0);
+
+/// The detected color support of the outputs that are not stdout, for the outputs that are no terminal and for the terminals.
+static OUTPUT_COLOR_SUPPORT: [AtomicU8; 2] = [AtomicU8::new(0), AtomicU8::new(0)];
+
+/// Get the color support the colors are written to stdout with, it is detected the first time it is used.
+pub fn color_support() -> ColorSupport {
+    load_color_support(&COLOR_SUPPORT, ColorSupport::detect)
+}
+
+/// Set the color support the colors are written to stdout with, instead of the detected color support.
+pub fn set_color_support(support: ColorSupport) {
+    COLOR_SUPPORT.store(support as u8 + 1, Ordering::SeqCst);
+}
+
+/// Get the color support of the output with the given target, stdout has the global `color_support()`.
+pub(crate) fn color_support_of(target: OutputTarget) -> ColorSupport {
+    if target.is_stdout {
+        return color_support();
+    }
+
+    load_color_support(&OUTPUT_COLOR_SUPPORT[target.is_tty as usize], || {
+        ColorSupport::from_env(|name| env::var(name).ok(), target.is_tty)
+    })
+}
+
+/// Get the color support that is stored in the given value, it is detected with the given function when it is not stored yet.
+fn load_color_support<F: FnOnce() -> ColorSupport>(stored: &AtomicU8, detect: F) -> ColorSupport {
+    match stored.load(Ordering::SeqCst) {
+        1 => ColorSupport::None,
+        2 => ColorSupport::Ansi16,
+        3 => ColorSupport::Ansi256,
+        4 => ColorSupport::TrueColor,
+        _ => {
+            let support = detect();
+            stored.store(support as u8 + 1, Ordering::SeqCst);
+            support
+        }
+    }
+}
+
+/// Decides whether colors and attributes are written, like the `--color=auto|always|never` option of many programs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum StylePolicy {
+    /// Write the styling when the output has color support, so not when the output is redirected or `NO_COLOR` is set.
+    #[default]
+    Auto,
+    /// Always write the styling, also when the output is no terminal.
+    Always,
+    /// Never write the styling, styled text is written as plain text.
+    Never,
+}
+
+impl StylePolicy {
+    /// Check if the styling should be written to stdout with this policy.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::StylePolicy;
+    ///
+    /// assert!(StylePolicy::Always.is_enabled());
+    /// assert!(!StylePolicy::Never.is_enabled());
+    /// ```
+    pub fn is_enabled(self) -> bool {
+        self.is_enabled_for(color_support())
+    }
+
+    /// Check if the styling should be written with this policy to an output with the given color support.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::{ColorSupport, StylePolicy};
+    ///
+    /// assert!(StylePolicy::Auto.is_enabled_for(ColorSupport::Ansi16));
+    /// assert!(!StylePolicy::Auto.is_enabled_for(ColorSupport::None));
+    /// ```
+    pub fn is_enabled_for(self, support: ColorSupport) -> bool {
+        match self {
+            StylePolicy::Auto => support != ColorSupport::None,
+            StylePolicy::Always => true,
+            StylePolicy::Never => false,
+        }
+    }
+}
+
+/// The global style policy, the index of the `StylePolicy`.
+static STYLE_POLICY: AtomicU8 = AtomicU8::new(StylePolicy::Auto as u8);
+
+/// Get the global style policy, which is `StylePolicy::Auto` unless it is changed with `set_style_policy()`.
+pub fn style_policy() -> StylePolicy {
+    match STYLE_POLICY.load(Ordering::SeqCst) {
+        1 => StylePolicy::Always,
+        2 => StylePolicy::Never,
+        _ => StylePolicy::Auto,
+    }
+}
+
+/// Set the global style policy, used by `StyledObject` and every `TerminalColor` that has no policy of its own.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{paint, set_style_policy, Color, StylePolicy};
+///
+/// set_style_policy(StylePolicy::Never);
+/// assert_eq!(paint("error").with(Color::Red).to_string(), "error");
+///
+/// set_style_policy(StylePolicy::Always);
+/// assert_eq!(paint("error").with(Color::Red).to_string(), "\x1B[38;5;9merror\x1B[0m");
+/// ```
+pub fn set_style_policy(policy: StylePolicy) {
+    STYLE_POLICY.store(policy as u8, Ordering::SeqCst);
+}
+
+/// The detected styled underline support, `0` when it is not detected yet, `1` when unsupported and `2` when supported.
+static STYLED_UNDERLINE_SUPPORT: AtomicU8 = AtomicU8::new(0);
+
+/// Check if the terminal supports the underline styles and underline colors, it is detected the first time it is used.
+///
+/// Without support the underline styles are written as an single underline and the underline color is not written,
+/// because some terminals read the parameters of those codes as other attributes.
+pub fn styled_underline_support() -> bool {
+    match STYLED_UNDERLINE_SUPPORT.load(Ordering::SeqCst) {
+        1 => false,
+        2 => true,
+        _ => {
+            let supported = detect_styled_underline(|name| env::var(name).ok());
+            set_styled_underline_support(supported);
+            supported
+        }
+    }
+}
+
+/// Set whether the underline styles and underline colors are written, instead of the detected support.
+pub fn set_styled_underline_support(supported: bool) {
+    STYLED_UNDERLINE_SUPPORT.store(if supported { 2 } else { 1 }, Ordering::SeqCst);
+}
+
+/// Detect the styled underline support from the given environment variables.
+fn detect_styled_underline<F>(var: F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let term = var("TERM").unwrap_or_default().to_lowercase();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+    // VTE supports the underline styles since version 0.51.2.
+    let vte_version = var("VTE_VERSION")
+        .and_then(|version| version.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    ["kitty", "wezterm", "foot", "ghostty", "alacritty"]
+        .iter()
+        .any(|name| term.contains(name) || term_program.contains(name))
+        || vte_version >= 5102
+}
+
+/// Get the named color that is nearest to the given color, for the outputs that can only show the named colors.
+///
+/// Palette index 8 has no name, so it is never the result.
+#[cfg(windows)]
+pub(crate) fn nearest_named(color: Color) -> Color {
+    let rgb = match color {
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(index) => palette_rgb(index),
+        _ => return color,
+    };
+
+    Color::from_ansi_index(nearest((0..=15).filter(|&index| index != 8), rgb))
+}
+
+/// Get the RGB value of the color with the given index of the 256 color palette, as xterm shows it.
+fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    const NAMED: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => NAMED[index as usize],
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE[(index / 36) as usize],
+                CUBE[(index / 6 % 6) as usize],
+                CUBE[(index % 6) as usize],
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Get the index of the palette color of the given indexes that is nearest to the given RGB value.
+fn nearest<I: Iterator<Item = u8>>(indexes: I, rgb: (u8, u8, u8)) -> u8 {
+    let distance = |index: &u8| {
+        let (r, g, b) = palette_rgb(*index);
+        let dr = i32::from(r) - i32::from(rgb.0);
+        let dg = i32::from(g) - i32::from(rgb.1);
+        let db = i32::from(b) - i32::from(rgb.2);
+        dr * dr + dg * dg + db * db
+    };
+
+    indexes.min_by_key(distance).unwrap_or(0)
+}
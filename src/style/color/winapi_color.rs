@@ -1,9 +1,13 @@
 use super::super::{Color, ColorType};
+use super::support::nearest_named;
 use super::ITerminalColor;
 use kernel::windows_kernel::kernel;
 use winapi::um::wincon;
 use Construct;
 
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+
 /// This struct is an windows implementation for color related actions.
 #[derive(Debug)]
 pub struct WinApiColor {
@@ -13,16 +17,35 @@ pub struct WinApiColor {
 impl Construct for WinApiColor {
     fn new() -> Box<WinApiColor> {
         Box::from(WinApiColor {
-            original_console_color: kernel::get_original_console_color(),
+            original_console_color: original_console_color(),
         })
     }
 }
 
+/// The console color from before any color was set, `u32::MAX` when it is not read yet.
+static ORIGINAL_CONSOLE_COLOR: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// Get the console color from before the first color change, so that every `WinApiColor` resets to the same color.
+fn original_console_color() -> u16 {
+    let color = ORIGINAL_CONSOLE_COLOR.load(Ordering::SeqCst);
+
+    if color != u32::MAX {
+        return color as u16;
+    }
+
+    // when the color can not be read the output is not a console, then there is nothing to reset.
+    let color = kernel::get_original_console_color().unwrap_or(0x0007);
+    ORIGINAL_CONSOLE_COLOR.store(u32::from(color), Ordering::SeqCst);
+    color
+}
+
 impl ITerminalColor for WinApiColor {
-    fn set_fg(&self, fg_color: Color) {
+    fn set_fg(&self, output: &mut dyn Write, fg_color: Color) -> io::Result<()> {
+        output.flush()?;
+
         let color_value = &self.color_value(fg_color, ColorType::Foreground);
 
-        let csbi = kernel::get_console_screen_buffer_info();
+        let csbi = kernel::get_console_screen_buffer_info()?;
 
         // Notice that the color values are stored in wAttribute.
         // So we need to use bitwise operators to check if the values exists or to get current console colors.
@@ -33,17 +56,19 @@ impl ITerminalColor for WinApiColor {
 
         // background intensity is a separate value in attrs,
         // wee need to check if this was applied to the current bg color.
-        if (attrs & wincon::BACKGROUND_INTENSITY as u16) != 0 {
-            color = color | wincon::BACKGROUND_INTENSITY as u16;
+        if (attrs & wincon::BACKGROUND_INTENSITY) != 0 {
+            color |= wincon::BACKGROUND_INTENSITY;
         }
 
-        kernel::set_console_text_attribute(color);
+        kernel::set_console_text_attribute(color)
     }
 
-    fn set_bg(&self, bg_color: Color) {
+    fn set_bg(&self, output: &mut dyn Write, bg_color: Color) -> io::Result<()> {
+        output.flush()?;
+
         let color_value = &self.color_value(bg_color, ColorType::Background);
 
-        let csbi = kernel::get_console_screen_buffer_info();
+        let csbi = kernel::get_console_screen_buffer_info()?;
         // Notice that the color values are stored in wAttribute.
         // So wee need to use bitwise operators to check if the values exists or to get current console colors.
         let mut color: u16;
@@ -53,22 +78,24 @@ impl ITerminalColor for WinApiColor {
 
         // Foreground intensity is a separate value in attrs,
         // So we need to check if this was applied to the current fg color.
-        if (attrs & wincon::FOREGROUND_INTENSITY as u16) != 0 {
-            color = color | wincon::FOREGROUND_INTENSITY as u16;
+        if (attrs & wincon::FOREGROUND_INTENSITY) != 0 {
+            color |= wincon::FOREGROUND_INTENSITY;
         }
 
-        kernel::set_console_text_attribute(color);
+        kernel::set_console_text_attribute(color)
     }
 
-    fn reset(&self) {
-        kernel::set_console_text_attribute(self.original_console_color);
+    fn reset(&self, output: &mut dyn Write) -> io::Result<()> {
+        output.flush()?;
+        kernel::set_console_text_attribute(self.original_console_color)
     }
 
     /// This will get the winapi color value from the Color and ColorType struct
     fn color_value(&self, color: Color, color_type: ColorType) -> String {
         use style::{Color, ColorType};
 
-        let winapi_color: u16;
+        // the console can only show the named colors.
+        let color = nearest_named(color);
 
         let fg_green = wincon::FOREGROUND_GREEN;
         let fg_red = wincon::FOREGROUND_RED;
@@ -80,45 +107,45 @@ impl ITerminalColor for WinApiColor {
         let bg_blue = wincon::BACKGROUND_BLUE;
         let bg_intensity = wincon::BACKGROUND_INTENSITY;
 
-        match color_type {
-            ColorType::Foreground => {
-                winapi_color = match color {
-                    Color::Black => 0,
-                    Color::Red => fg_intensity | fg_red,
-                    Color::DarkRed => fg_red,
-                    Color::Green => fg_intensity | fg_green,
-                    Color::DarkGreen => fg_green,
-                    Color::Yellow => fg_intensity | fg_green | fg_red,
-                    Color::DarkYellow => fg_green | fg_red,
-                    Color::Blue => fg_intensity | fg_blue,
-                    Color::DarkBlue => fg_blue,
-                    Color::Magenta => fg_intensity | fg_red | fg_blue,
-                    Color::DarkMagenta => fg_red | fg_blue,
-                    Color::Cyan => fg_intensity | fg_green | fg_blue,
-                    Color::DarkCyan => fg_green | fg_blue,
-                    Color::Grey => fg_intensity,
-                    Color::White => fg_intensity | fg_red | fg_green | fg_blue,
-                };
-            }
-            ColorType::Background => {
-                winapi_color = match color {
-                    Color::Black => 0,
-                    Color::Red => bg_intensity | bg_red,
-                    Color::DarkRed => bg_red,
-                    Color::Green => bg_intensity | bg_green,
-                    Color::DarkGreen => bg_green,
-                    Color::Yellow => bg_intensity | bg_green | bg_red,
-                    Color::DarkYellow => bg_green | bg_red,
-                    Color::Blue => bg_intensity | bg_blue,
-                    Color::DarkBlue => bg_blue,
-                    Color::Magenta => bg_intensity | bg_red | bg_blue,
-                    Color::DarkMagenta => bg_red | bg_blue,
-                    Color::Cyan => bg_intensity | bg_green | bg_blue,
-                    Color::DarkCyan => bg_green | bg_blue,
-                    Color::Grey => bg_intensity,
-                    Color::White => bg_intensity | bg_red | bg_green | bg_blue,
-                };
-            }
+        let winapi_color: u16 = match color_type {
+            ColorType::Foreground => match color {
+                Color::Black => 0,
+                Color::Red => fg_intensity | fg_red,
+                Color::DarkRed => fg_red,
+                Color::Green => fg_intensity | fg_green,
+                Color::DarkGreen => fg_green,
+                Color::Yellow => fg_intensity | fg_green | fg_red,
+                Color::DarkYellow => fg_green | fg_red,
+                Color::Blue => fg_intensity | fg_blue,
+                Color::DarkBlue => fg_blue,
+                Color::Magenta => fg_intensity | fg_red | fg_blue,
+                Color::DarkMagenta => fg_red | fg_blue,
+                Color::Cyan => fg_intensity | fg_green | fg_blue,
+                Color::DarkCyan => fg_green | fg_blue,
+                Color::Grey => fg_intensity,
+                Color::White => fg_intensity | fg_red | fg_green | fg_blue,
+                Color::Rgb { .. } | Color::AnsiValue(_) => fg_red | fg_green | fg_blue,
+            },
+            ColorType::Background => match color {
+                Color::Black => 0,
+                Color::Red => bg_intensity | bg_red,
+                Color::DarkRed => bg_red,
+                Color::Green => bg_intensity | bg_green,
+                Color::DarkGreen => bg_green,
+                Color::Yellow => bg_intensity | bg_green | bg_red,
+                Color::DarkYellow => bg_green | bg_red,
+                Color::Blue => bg_intensity | bg_blue,
+                Color::DarkBlue => bg_blue,
+                Color::Magenta => bg_intensity | bg_red | bg_blue,
+                Color::DarkMagenta => bg_red | bg_blue,
+                Color::Cyan => bg_intensity | bg_green | bg_blue,
+                Color::DarkCyan => bg_green | bg_blue,
+                Color::Grey => bg_intensity,
+                Color::White => bg_intensity | bg_red | bg_green | bg_blue,
+                Color::Rgb { .. } | Color::AnsiValue(_) => 0,
+            },
+            // the windows console has no underline colors.
+            ColorType::Underline => 0,
         };
 
         winapi_color.to_string()
@@ -3,17 +3,57 @@
 
 mod color;
 mod styles;
+mod text;
 
 pub use self::color::color::{color, paint, TerminalColor};
+pub use self::color::commands::{
+    ResetColor, SetAttr, SetBg, SetFg, SetUnderlineColor, SetUnderlineStyle,
+};
+pub use self::color::support::{
+    color_support, set_color_support, set_style_policy, set_styled_underline_support, style_policy,
+    styled_underline_support, ColorSupport, StylePolicy,
+};
+pub(crate) use self::color::AnsiColor;
+pub use self::color::ITerminalColor;
 
 pub use self::styles::objectstyle::ObjectStyle;
 pub use self::styles::styledobject::StyledObject;
+pub use self::styles::stylize::Stylize;
+pub use self::text::{display_width, fit, pad, strip_ansi_codes, truncate};
 
 use std::convert::From;
+use std::fmt;
 use std::str::FromStr;
+use {Error, ErrorKind};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd)]
+/// The attributes that can be applied to the text, the value is the `SGR` parameter the attribute is written with.
+///
+/// Every attribute has an counterpart that removes it again, like `NoBold` for `Bold`,
+/// so an attribute can be removed without resetting the colors.
+/// Not every terminal supports every attribute, the attributes an terminal does not know are ignored by it.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{paint, set_style_policy, Attribute, Color, StylePolicy};
+///
+/// set_style_policy(StylePolicy::Always);
+///
+/// // `NoBold` ends bold text without resetting the red color.
+/// let styled = paint("normal").with(Color::Red).attr(Attribute::NoBold);
+/// assert_eq!(styled.to_string(), "\x1B[38;5;9m\x1B[22mnormal\x1B[0m");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Attribute {
+    /// Reset all attributes and colors to the terminal default.
+    Reset = 0,
     Bold = 1,
     Dim = 2,
     Italic = 3,
@@ -23,10 +63,80 @@ pub enum Attribute {
     Reverse = 7,
     Hidden = 8,
     CrossedOut = 9,
+    DoubleUnderlined = 21,
+    /// Normal intensity, removes `Bold` and `Dim`.
+    NoBold = 22,
+    /// Removes `Italic`.
+    NoItalic = 23,
+    /// Removes `Underlined` and `DoubleUnderlined`.
+    NoUnderline = 24,
+    /// Removes `SlowBlink` and `RapidBlink`.
+    NoBlink = 25,
+    /// Removes `Reverse`.
+    NoReverse = 27,
+    /// Removes `Hidden`.
+    NoHidden = 28,
+    /// Removes `CrossedOut`.
+    NotCrossedOut = 29,
+    Framed = 51,
+    Encircled = 52,
+    Overlined = 53,
+    /// Removes `Framed` and `Encircled`.
+    NotFramedOrEncircled = 54,
+    /// Removes `Overlined`.
+    NotOverlined = 55,
+}
+
+impl Attribute {
+    /// Check if this attribute removes attributes instead of adding one, like `NoBold` and `Reset`.
+    pub(crate) fn is_removal(self) -> bool {
+        matches!(self as u8, 0 | 22..=29 | 54 | 55)
+    }
+
+    /// Check if this attribute removes the given attribute, like `NoBold` removes `Bold` and `Dim`.
+    pub(crate) fn removes(self, attr: Attribute) -> bool {
+        use self::Attribute::*;
+
+        matches!(
+            (self, attr),
+            (Reset, _)
+                | (NoBold, Bold)
+                | (NoBold, Dim)
+                | (NoItalic, Italic)
+                | (NoUnderline, Underlined)
+                | (NoUnderline, DoubleUnderlined)
+                | (NoBlink, SlowBlink)
+                | (NoBlink, RapidBlink)
+                | (NoReverse, Reverse)
+                | (NoHidden, Hidden)
+                | (NotCrossedOut, CrossedOut)
+                | (NotFramedOrEncircled, Framed)
+                | (NotFramedOrEncircled, Encircled)
+                | (NotOverlined, Overlined)
+        )
+    }
+}
+
+/// The styles the underline can be drawn with, written as the sub-parameter of `CSI 4:n m`.
+///
+/// Not every terminal supports underline styles, the text is underlined with an single line by the terminals that do not.
+/// See `styled_underline_support()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum UnderlineStyle {
+    Single = 1,
+    Double = 2,
+    Curly = 3,
+    Dotted = 4,
+    Dashed = 5,
 }
 
 /// Colors that are available for coloring the termainal font.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Color {
     Black,
 
@@ -51,58 +161,465 @@ pub enum Color {
     Grey,
     White,
 
-    #[cfg(unix)]
+    /// An RGB color, the terminals that can not show it use the nearest color they can show.
     Rgb {
         r: u8,
         g: u8,
         b: u8,
     },
-    #[cfg(unix)]
+    /// An color of the 256 color palette, the terminals that can not show it use the nearest color they can show.
     AnsiValue(u8),
 }
 
-/// Color types that can be used to determine if the Color enum is an Fore- or Background Color
+/// Color types that can be used to determine if the Color enum is an Fore-, Background or underline Color
 #[derive(Debug, Copy, Clone)]
 pub enum ColorType {
     Background,
     Foreground,
+    Underline,
+}
+
+impl Color {
+    /// Get the color with the given index of the 256 color palette, the first 16 colors are the named colors.
+    pub(crate) fn from_ansi_index(index: u8) -> Color {
+        // the same indexes `AnsiColor` writes the named colors with.
+        match index {
+            0 => Color::Black,
+            1 => Color::DarkRed,
+            2 => Color::DarkGreen,
+            3 => Color::DarkYellow,
+            4 => Color::DarkBlue,
+            5 => Color::DarkMagenta,
+            6 => Color::DarkCyan,
+            7 => Color::White,
+            9 => Color::Red,
+            10 => Color::Green,
+            11 => Color::Yellow,
+            12 => Color::Blue,
+            13 => Color::Magenta,
+            14 => Color::Cyan,
+            15 => Color::Grey,
+            _ => Color::AnsiValue(index),
+        }
+    }
 }
 
-impl<'a> From<&'a str> for Color {
+/// Parse the color from the given text, an color that can not be parsed becomes `Color::White`.
+///
+/// Use `str::parse()` to find out if the text is an valid color.
+impl From<&str> for Color {
     fn from(src: &str) -> Self {
         src.parse().unwrap_or(Color::White)
     }
 }
 
+/// Parse the color from the given text, an color that can not be parsed becomes `Color::White`.
+///
+/// Use `str::parse()` to find out if the text is an valid color.
 impl From<String> for Color {
     fn from(src: String) -> Self {
         src.parse().unwrap_or(Color::White)
     }
 }
 
+/// Parse an color from an theme or configuration file.
+///
+/// The following formats are accepted, case insensitive and surrounded by whitespace:
+/// - The names of the colors, `dark` can be separated from the color by one `_`, `-` or space and `gray` is the same as `grey`,
+///   like `red`, `dark_red`, `darkred` and `dark-red`.
+/// - `#rrggbb` and `#rgb` for an `Color::Rgb`.
+/// - `rgb(r, g, b)` with the components from 0 to 255 for an `Color::Rgb`.
+/// - `ansi(n)` or just the number `n` from 0 to 255 for an `Color::AnsiValue`.
+///
+/// The error is an `ErrorKind::InvalidInput` that describes why the text is no color.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::Color;
+/// use self::crossterm::{Error, ErrorKind};
+///
+/// assert_eq!("dark-red".parse::<Color>().unwrap(), Color::DarkRed);
+/// assert_eq!("#ff8700".parse::<Color>().unwrap(), Color::Rgb { r: 255, g: 135, b: 0 });
+/// assert_eq!("rgb(255, 135, 0)".parse::<Color>().unwrap(), Color::Rgb { r: 255, g: 135, b: 0 });
+/// assert_eq!("ansi(208)".parse::<Color>().unwrap(), Color::AnsiValue(208));
+///
+/// let error = "redd".parse::<Color>().unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::InvalidInput);
+/// assert_eq!(error.to_string(), "invalid color `redd`: unknown color name");
+/// ```
 impl FromStr for Color {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(src: &str) -> Result<Self, Self::Err> {
-        let src = src.to_lowercase();
-
-        match src.as_ref() {
-            "black" => Ok(Color::Black),
-            "red" => Ok(Color::Red),
-            "dark_red" => Ok(Color::DarkRed),
-            "green" => Ok(Color::Green),
-            "dark_green" => Ok(Color::DarkGreen),
-            "yellow" => Ok(Color::Yellow),
-            "dark_yellow" => Ok(Color::DarkYellow),
-            "blue" => Ok(Color::Blue),
-            "dark_blue" => Ok(Color::DarkBlue),
-            "magenta" => Ok(Color::Magenta),
-            "dark_magenta" => Ok(Color::DarkMagenta),
-            "cyan" => Ok(Color::Cyan),
-            "dark_cyan" => Ok(Color::DarkCyan),
-            "grey" => Ok(Color::Grey),
-            "white" => Ok(Color::White),
-            _ => Ok(Color::White),
+        let src = src.trim();
+        let lower = src.to_lowercase();
+
+        let color = if let Some(hex) = lower.strip_prefix('#') {
+            parse_hex(hex)
+        } else if let Some(args) = function_args(&lower, "rgb") {
+            parse_rgb(args)
+        } else if let Some(args) = function_args(&lower, "ansi") {
+            parse_ansi(args)
+        } else if lower.chars().all(|ch| ch.is_ascii_digit()) && !lower.is_empty() {
+            parse_ansi(&lower)
+        } else {
+            parse_name(&lower)
+        };
+
+        color.map_err(|reason| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid color `{}`: {}", src, reason),
+            )
+        })
+    }
+}
+
+/// Format the color in the notation `FromStr` parses, so the text can be parsed back into the same color.
+///
+/// The named colors are written as their name, `Color::Rgb` as `#rrggbb` and `Color::AnsiValue` as `ansi(n)`.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::Color;
+///
+/// assert_eq!(Color::DarkRed.to_string(), "dark_red");
+/// assert_eq!(Color::Rgb { r: 255, g: 135, b: 0 }.to_string(), "#ff8700");
+/// assert_eq!(Color::AnsiValue(208).to_string(), "ansi(208)");
+/// ```
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Color::Black => f.write_str("black"),
+            Color::Red => f.write_str("red"),
+            Color::DarkRed => f.write_str("dark_red"),
+            Color::Green => f.write_str("green"),
+            Color::DarkGreen => f.write_str("dark_green"),
+            Color::Yellow => f.write_str("yellow"),
+            Color::DarkYellow => f.write_str("dark_yellow"),
+            Color::Blue => f.write_str("blue"),
+            Color::DarkBlue => f.write_str("dark_blue"),
+            Color::Magenta => f.write_str("magenta"),
+            Color::DarkMagenta => f.write_str("dark_magenta"),
+            Color::Cyan => f.write_str("cyan"),
+            Color::DarkCyan => f.write_str("dark_cyan"),
+            Color::Grey => f.write_str("grey"),
+            Color::White => f.write_str("white"),
+            Color::Rgb { r, g, b } => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            Color::AnsiValue(index) => write!(f, "ansi({})", index),
+        }
+    }
+}
+
+/// The color is serialized as the text `Display` writes, like `"dark_red"` or `"#ff8700"`.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+/// extern crate serde_json;
+///
+/// use self::crossterm::style::Color;
+///
+/// let json = serde_json::to_string(&Color::Rgb { r: 255, g: 135, b: 0 }).unwrap();
+/// assert_eq!(json, "\"#ff8700\"");
+/// ```
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Color {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The color is deserialized from any text `FromStr` parses, or from an number for an `Color::AnsiValue`.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+/// extern crate serde_json;
+///
+/// use self::crossterm::style::Color;
+///
+/// assert_eq!(serde_json::from_str::<Color>("\"dark-red\"").unwrap(), Color::DarkRed);
+/// assert_eq!(serde_json::from_str::<Color>("208").unwrap(), Color::AnsiValue(208));
+/// assert!(serde_json::from_str::<Color>("\"redd\"").is_err());
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Color {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        struct ColorVisitor;
+
+        impl<'de> ::serde::de::Visitor<'de> for ColorVisitor {
+            type Value = Color;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an color name, `#rrggbb`, `rgb(r, g, b)`, `ansi(n)` or an number from 0 to 255")
+            }
+
+            fn visit_str<E: ::serde::de::Error>(self, value: &str) -> Result<Color, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: ::serde::de::Error>(self, value: u64) -> Result<Color, E> {
+                value.to_string().parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: ::serde::de::Error>(self, value: i64) -> Result<Color, E> {
+                value.to_string().parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ColorVisitor)
+    }
+}
+
+/// Get the text between the parentheses of `name(...)`.
+fn function_args<'a>(src: &'a str, name: &str) -> Option<&'a str> {
+    src.strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Parse the name of an color, `dark` can be separated from the color by one `_`, `-` or space.
+fn parse_name(src: &str) -> Result<Color, String> {
+    let (dark, name) = match src.strip_prefix("dark") {
+        Some(rest) => (
+            true,
+            rest.strip_prefix(&['_', '-', ' '][..]).unwrap_or(rest),
+        ),
+        None => (false, src),
+    };
+
+    match (dark, name) {
+        (false, "black") => Ok(Color::Black),
+        (false, "red") => Ok(Color::Red),
+        (true, "red") => Ok(Color::DarkRed),
+        (false, "green") => Ok(Color::Green),
+        (true, "green") => Ok(Color::DarkGreen),
+        (false, "yellow") => Ok(Color::Yellow),
+        (true, "yellow") => Ok(Color::DarkYellow),
+        (false, "blue") => Ok(Color::Blue),
+        (true, "blue") => Ok(Color::DarkBlue),
+        (false, "magenta") => Ok(Color::Magenta),
+        (true, "magenta") => Ok(Color::DarkMagenta),
+        (false, "cyan") => Ok(Color::Cyan),
+        (true, "cyan") => Ok(Color::DarkCyan),
+        (false, "grey") | (false, "gray") => Ok(Color::Grey),
+        (false, "white") => Ok(Color::White),
+        (false, "") => Err("the color is empty".to_string()),
+        _ => Err("unknown color name".to_string()),
+    }
+}
+
+/// Parse the hexadecimal `rrggbb` or `rgb` part of an `#rrggbb` or `#rgb` color.
+fn parse_hex(src: &str) -> Result<Color, String> {
+    if !src.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err("expected hexadecimal digits after `#`".to_string());
+    }
+
+    let component = |digits: &str| u8::from_str_radix(digits, 16).unwrap_or(0);
+
+    match src.len() {
+        6 => Ok(Color::Rgb {
+            r: component(&src[0..2]),
+            g: component(&src[2..4]),
+            b: component(&src[4..6]),
+        }),
+        // every digit is repeated, so `#f80` is the same as `#ff8800`.
+        3 => Ok(Color::Rgb {
+            r: component(&src[0..1]) * 17,
+            g: component(&src[1..2]) * 17,
+            b: component(&src[2..3]) * 17,
+        }),
+        _ => Err("expected 3 or 6 hexadecimal digits after `#`".to_string()),
+    }
+}
+
+/// Parse the `r, g, b` arguments of an `rgb(r, g, b)` color.
+fn parse_rgb(args: &str) -> Result<Color, String> {
+    let components = args
+        .split(',')
+        .map(|component| parse_u8(component.trim()))
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    match components[..] {
+        [r, g, b] => Ok(Color::Rgb { r, g, b }),
+        _ => Err(format!(
+            "expected 3 components in `rgb()`, got {}",
+            components.len()
+        )),
+    }
+}
+
+/// Parse the index of an `ansi(n)` color or an plain number.
+fn parse_ansi(src: &str) -> Result<Color, String> {
+    parse_u8(src.trim()).map(Color::AnsiValue)
+}
+
+/// Parse an number from 0 to 255.
+fn parse_u8(src: &str) -> Result<u8, String> {
+    src.parse::<u8>().map_err(|_| {
+        if !src.is_empty() && src.chars().all(|ch| ch.is_ascii_digit()) {
+            format!("`{}` is out of the range 0 to 255", src)
+        } else {
+            format!("`{}` is no number from 0 to 255", src)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+    use ErrorKind;
+
+    /// Get the message of the error parsing the given text, it panics when the text is an valid color.
+    fn error(src: &str) -> String {
+        let error = src.parse::<Color>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        error.to_string()
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!("black".parse::<Color>().unwrap(), Color::Black);
+        assert_eq!(
+            " Dark Magenta ".parse::<Color>().unwrap(),
+            Color::DarkMagenta
+        );
+        assert_eq!("DARK_CYAN".parse::<Color>().unwrap(), Color::DarkCyan);
+        assert_eq!("gray".parse::<Color>().unwrap(), Color::Grey);
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert_eq!(
+            error("purple"),
+            "invalid color `purple`: unknown color name"
+        );
+        assert_eq!(error("  "), "invalid color ``: the color is empty");
+    }
+
+    #[test]
+    fn rejects_separators_inside_words() {
+        assert_eq!("darkred".parse::<Color>().unwrap(), Color::DarkRed);
+        assert_eq!("dark-red".parse::<Color>().unwrap(), Color::DarkRed);
+
+        assert_eq!(error("r-e-d"), "invalid color `r-e-d`: unknown color name");
+        assert_eq!(
+            error("dar k red"),
+            "invalid color `dar k red`: unknown color name"
+        );
+        assert_eq!(
+            error("dark__red"),
+            "invalid color `dark__red`: unknown color name"
+        );
+        assert_eq!(error("dark"), "invalid color `dark`: unknown color name");
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(
+            "#FF8700".parse::<Color>().unwrap(),
+            Color::Rgb {
+                r: 255,
+                g: 135,
+                b: 0
+            }
+        );
+        assert_eq!(
+            "#f80".parse::<Color>().unwrap(),
+            Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_hex_colors() {
+        assert_eq!(
+            error("#ff870g"),
+            "invalid color `#ff870g`: expected hexadecimal digits after `#`"
+        );
+        assert_eq!(
+            error("#ff87"),
+            "invalid color `#ff87`: expected 3 or 6 hexadecimal digits after `#`"
+        );
+        assert_eq!(
+            error("#"),
+            "invalid color `#`: expected 3 or 6 hexadecimal digits after `#`"
+        );
+        assert_eq!(
+            error("#+f+"),
+            "invalid color `#+f+`: expected hexadecimal digits after `#`"
+        );
+    }
+
+    #[test]
+    fn parses_rgb_colors() {
+        assert_eq!(
+            "RGB( 1,2 , 3 )".parse::<Color>().unwrap(),
+            Color::Rgb { r: 1, g: 2, b: 3 }
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_rgb_colors() {
+        assert_eq!(
+            error("rgb(256,0,0)"),
+            "invalid color `rgb(256,0,0)`: `256` is out of the range 0 to 255"
+        );
+        assert_eq!(
+            error("rgb(-1,0,0)"),
+            "invalid color `rgb(-1,0,0)`: `-1` is no number from 0 to 255"
+        );
+        assert_eq!(
+            error("rgb(1,2)"),
+            "invalid color `rgb(1,2)`: expected 3 components in `rgb()`, got 2"
+        );
+    }
+
+    #[test]
+    fn parses_ansi_colors() {
+        assert_eq!(
+            "ansi( 208 )".parse::<Color>().unwrap(),
+            Color::AnsiValue(208)
+        );
+        assert_eq!("0".parse::<Color>().unwrap(), Color::AnsiValue(0));
+    }
+
+    #[test]
+    fn rejects_malformed_ansi_colors() {
+        assert_eq!(
+            error("256"),
+            "invalid color `256`: `256` is out of the range 0 to 255"
+        );
+        assert_eq!(
+            error("ansi()"),
+            "invalid color `ansi()`: `` is no number from 0 to 255"
+        );
+    }
+
+    #[test]
+    fn parses_the_text_it_is_displayed_as() {
+        let colors = [
+            Color::DarkYellow,
+            Color::Grey,
+            Color::Rgb { r: 1, g: 2, b: 3 },
+            Color::AnsiValue(17),
+        ];
+
+        for color in colors.iter() {
+            assert_eq!(color.to_string().parse::<Color>().unwrap(), *color);
         }
     }
 }
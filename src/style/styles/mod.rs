@@ -1,4 +1,6 @@
 //! This module contains the modules that are responsible for storing the styling displayable objects or simply set text.
 
 pub mod objectstyle;
+#[macro_use]
 pub mod styledobject;
+pub mod stylize;
@@ -1,30 +1,47 @@
 //! This module contains the `object style` that can be applied to an `styled object`.
 
 use std::fmt::Display;
-use style::{Color, StyledObject};
+use style::{Color, StyledObject, UnderlineStyle};
 
-#[cfg(unix)]
 use super::super::Attribute;
 
 /// Struct that contains the style properties that can be applied to an displayable object.
-#[derive(Clone)]
+///
+/// The properties that are not set are inherited from the text around it, so the default style changes nothing.
+/// Styles can be layered with `patch()`, for example an focused style over an base style.
+///
+/// With the `serde` feature the style is (de)serialized as an map with the optional fields
+/// `fg_color`, `bg_color`, `attrs`, `underline_style` and `underline_color`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ObjectStyle {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub fg_color: Option<Color>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub bg_color: Option<Color>,
 
-    #[cfg(unix)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub attrs: Vec<Attribute>,
-}
 
-impl Default for ObjectStyle {
-    fn default() -> ObjectStyle {
-        ObjectStyle {
-            fg_color: Some(Color::White),
-            bg_color: Some(Color::Black),
-            #[cfg(unix)]
-            attrs: Vec::new(),
-        }
-    }
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub underline_style: Option<UnderlineStyle>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub underline_color: Option<Color>,
 }
 
 impl ObjectStyle {
@@ -41,12 +58,13 @@ impl ObjectStyle {
 
     /// Get an new instance of `ObjectStyle`
     pub fn new() -> ObjectStyle {
-        return ObjectStyle {
+        ObjectStyle {
             fg_color: None,
             bg_color: None,
-            #[cfg(unix)]
             attrs: Vec::new(),
-        };
+            underline_style: None,
+            underline_color: None,
+        }
     }
 
     /// Set the background color of `ObjectStyle` to the passed color.
@@ -61,8 +79,178 @@ impl ObjectStyle {
         self
     }
 
-    #[cfg(unix)]
+    /// Underline the text of `ObjectStyle` with the passed style.
+    pub fn underline_style(mut self, style: UnderlineStyle) -> ObjectStyle {
+        self.underline_style = Some(style);
+        self
+    }
+
+    /// Set the underline color of `ObjectStyle` to the passed color, the text is only underlined with an underline style.
+    ///
+    /// The color is written in the colon form `58:2::r:g:b` or `58:5:n` instead of the semicolon form `58;2;r;g;b`.
+    /// An terminal that does not know underline colors ignores the colon form as a whole,
+    /// while it would read the numbers of the semicolon form as separate attributes, like `2` for dim text.
+    pub fn underline_color(mut self, color: Color) -> ObjectStyle {
+        self.underline_color = Some(color);
+        self
+    }
+
+    /// Layer the passed style over `ObjectStyle`, the properties the overlay sets replace the ones of this style
+    /// and the properties it does not set are inherited from this style.
+    ///
+    /// The attributes of the overlay are added, the attributes that remove others like `NoBold` remove them from this style
+    /// and are kept, so they also remove the attributes of the text around the style. `Attribute::Reset` also drops the inherited colors.
+    /// Because of that it does not matter how the styles are grouped, `a.patch(b).patch(c)` is the same as `a.patch(b.patch(c))`.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::{Attribute, Color, ObjectStyle};
+    ///
+    /// let mut base = ObjectStyle::new().fg(Color::Grey).bg(Color::Black);
+    /// base.add_attr(Attribute::Bold);
+    ///
+    /// let mut selected = ObjectStyle::new().bg(Color::DarkBlue);
+    /// selected.add_attr(Attribute::NoBold);
+    ///
+    /// let style = base.patch(selected);
+    ///
+    /// assert_eq!(style.fg_color, Some(Color::Grey));
+    /// assert_eq!(style.bg_color, Some(Color::DarkBlue));
+    /// assert_eq!(style.attrs, vec![Attribute::NoBold]);
+    /// ```
+    pub fn patch(mut self, overlay: ObjectStyle) -> ObjectStyle {
+        // the attributes go first, so an reset does not drop the colors of the overlay.
+        for attr in overlay.attrs {
+            self.layer_attr(attr);
+        }
+
+        if overlay.fg_color.is_some() {
+            self.fg_color = overlay.fg_color;
+        }
+        if overlay.bg_color.is_some() {
+            self.bg_color = overlay.bg_color;
+        }
+        if overlay.underline_style.is_some() {
+            self.underline_style = overlay.underline_style;
+        }
+        if overlay.underline_color.is_some() {
+            self.underline_color = overlay.underline_color;
+        }
+
+        self
+    }
+
+    /// Layer the passed attribute over the attributes of `ObjectStyle`, the attributes it removes are dropped and the attribute is kept.
+    fn layer_attr(&mut self, attr: Attribute) {
+        if attr == Attribute::Reset {
+            self.fg_color = None;
+            self.bg_color = None;
+            self.underline_color = None;
+        }
+        if attr.removes(Attribute::Underlined) {
+            self.underline_style = None;
+        }
+
+        self.attrs
+            .retain(|current| *current != attr && !attr.removes(*current));
+        self.attrs.push(attr);
+    }
+
+    /// Apply the passed attribute the way the terminal does, the attributes it removes are removed
+    /// instead of adding the removing attribute.
+    pub(crate) fn apply_attr(&mut self, attr: Attribute) {
+        if attr == Attribute::Reset {
+            *self = ObjectStyle::new();
+            return;
+        }
+
+        self.attrs.retain(|current| !attr.removes(*current));
+
+        if attr.removes(Attribute::Underlined) {
+            self.underline_style = None;
+        }
+        if !attr.is_removal() && !self.attrs.contains(&attr) {
+            self.attrs.push(attr);
+        }
+    }
+
+    /// Add the passed attribute to `ObjectStyle`, the windows console without `ANSI escape codes` ignores the attributes.
     pub fn add_attr(&mut self, attr: Attribute) {
         self.attrs.push(attr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectStyle;
+    use style::{Attribute, Color, UnderlineStyle};
+
+    fn style(attrs: &[Attribute]) -> ObjectStyle {
+        let mut style = ObjectStyle::new();
+        for attr in attrs {
+            style.add_attr(*attr);
+        }
+        style
+    }
+
+    #[test]
+    fn patching_the_default_style_changes_nothing() {
+        let styles = vec![
+            style(&[Attribute::NoBold, Attribute::Italic, Attribute::NoUnderline])
+                .fg(Color::Red)
+                .underline_color(Color::Blue),
+            style(&[Attribute::Reset, Attribute::Bold]).bg(Color::DarkCyan),
+            style(&[Attribute::NoItalic]).underline_style(UnderlineStyle::Curly),
+        ];
+
+        for style in styles {
+            assert_eq!(ObjectStyle::new().patch(style.clone()), style);
+        }
+    }
+
+    #[test]
+    fn keeps_the_removing_attributes() {
+        let bold = style(&[Attribute::Bold, Attribute::Dim]).fg(Color::Grey);
+        let normal = bold.clone().patch(style(&[Attribute::NoBold]));
+
+        assert_eq!(normal.attrs, vec![Attribute::NoBold]);
+        assert_eq!(normal.fg_color, Some(Color::Grey));
+
+        let reset = bold.patch(style(&[Attribute::Reset]).bg(Color::Blue));
+        assert_eq!(reset, style(&[Attribute::Reset]).bg(Color::Blue));
+    }
+
+    #[test]
+    fn layering_does_not_depend_on_the_grouping() {
+        let base = style(&[Attribute::Bold, Attribute::Underlined])
+            .fg(Color::Grey)
+            .bg(Color::Black)
+            .underline_style(UnderlineStyle::Curly);
+        let selected = style(&[Attribute::NoBold, Attribute::Reverse]).bg(Color::DarkBlue);
+        let focused = style(&[
+            Attribute::Bold,
+            Attribute::NoUnderline,
+            Attribute::NoReverse,
+        ])
+        .fg(Color::White);
+
+        let layered = base.clone().patch(selected.clone()).patch(focused.clone());
+
+        assert_eq!(layered, base.patch(selected.patch(focused)));
+        assert_eq!(
+            layered.attrs,
+            vec![
+                Attribute::NoBold,
+                Attribute::Bold,
+                Attribute::NoUnderline,
+                Attribute::NoReverse
+            ]
+        );
+        assert_eq!(layered.fg_color, Some(Color::White));
+        assert_eq!(layered.bg_color, Some(Color::DarkBlue));
+        assert_eq!(layered.underline_style, None);
+    }
+}
@@ -1,19 +1,77 @@
 //! This module contains the logic to style an object that contains some context witch can be styled.
 
-use std::io::Write;
-use std::{self, fmt};
-
-#[cfg(unix)]
 use super::super::Attribute;
+use super::super::ITerminalColor;
+#[cfg(windows)]
+use kernel::windows_kernel::ansi_support;
+use style::{style_policy, AnsiColor, Color, ColorType, ObjectStyle, UnderlineStyle};
 
-use style::{Color, ObjectStyle};
+use std::fmt;
+#[cfg(windows)]
+use std::io::{self, Write};
 
 /// Struct that contains both the style and the content wits can be styled.
+///
+/// The colors and attributes are written as `ANSI escape codes` together with the content when it is formatted,
+/// so an styled object can be printed, but also be formatted into an `String`, written to an file or added to an log line.
+/// When the `style_policy()` disables the styling only the content is written,
+/// by default this is the case when stdout is no terminal or `NO_COLOR` is set.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+/// use self::crossterm::style::{paint, set_style_policy, Color, StylePolicy};
+///
+/// set_style_policy(StylePolicy::Always);
+/// let styled = format!("{}", paint("error").with(Color::Red));
+///
+/// assert_eq!(styled, "\x1B[38;5;9merror\x1B[0m");
+/// ```
 pub struct StyledObject<D> {
     pub object_style: ObjectStyle,
     pub content: D,
 }
 
+/// Generate the methods that set the named colors as foreground (`red()`) or background (`on_red()`) color,
+/// for `StyledObject` and the `Stylize` trait.
+macro_rules! color_methods {
+    ($vis:vis $styled:ty) => {
+        color_methods!($vis $styled;
+            black, on_black => Black;
+            red, on_red => Red;
+            dark_red, on_dark_red => DarkRed;
+            green, on_green => Green;
+            dark_green, on_dark_green => DarkGreen;
+            yellow, on_yellow => Yellow;
+            dark_yellow, on_dark_yellow => DarkYellow;
+            blue, on_blue => Blue;
+            dark_blue, on_dark_blue => DarkBlue;
+            magenta, on_magenta => Magenta;
+            dark_magenta, on_dark_magenta => DarkMagenta;
+            cyan, on_cyan => Cyan;
+            dark_cyan, on_dark_cyan => DarkCyan;
+            grey, on_grey => Grey;
+            white, on_white => White;
+        );
+    };
+    ($vis:vis $styled:ty; $($fg:ident, $bg:ident => $color:ident;)*) => {
+        $(
+            #[doc = concat!("Set the foreground color to `Color::", stringify!($color), "`.")]
+            #[inline(always)]
+            $vis fn $fg(self) -> $styled {
+                self.with(Color::$color)
+            }
+
+            #[doc = concat!("Set the background color to `Color::", stringify!($color), "`.")]
+            #[inline(always)]
+            $vis fn $bg(self) -> $styled {
+                self.on(Color::$color)
+            }
+        )*
+    };
+}
+
 impl<D> StyledObject<D> {
     /// Set the foreground of the styled object to the passed `Color`
     ///
@@ -77,105 +135,174 @@ impl<D> StyledObject<D> {
     /// println!("{}", paint("Bold").attr(Attribute::Bold));
     ///
     /// ```
-    #[cfg(unix)]
     pub fn attr(mut self, attr: Attribute) -> StyledObject<D> {
-        &self.object_style.add_attr(attr);
+        self.object_style.add_attr(attr);
+        self
+    }
+
+    /// Underline the styled object with the passed `UnderlineStyle`.
+    ///
+    /// The terminals that do not support underline styles draw an single underline.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    /// use self::crossterm::style::{
+    ///     paint, set_style_policy, set_styled_underline_support, Color, StylePolicy, UnderlineStyle,
+    /// };
+    ///
+    /// set_style_policy(StylePolicy::Always);
+    /// set_styled_underline_support(true);
+    ///
+    /// // an red squiggle under the error.
+    /// let error = paint("error")
+    ///     .underline_style(UnderlineStyle::Curly)
+    ///     .underline_color(Color::DarkRed);
+    /// assert_eq!(error.to_string(), "\x1B[4m\x1B[4:3m\x1B[58:5:1merror\x1B[0m");
+    ///
+    /// // only an single underline when the terminal does not support the styles.
+    /// set_styled_underline_support(false);
+    /// assert_eq!(error.to_string(), "\x1B[4merror\x1B[0m");
+    /// ```
+    pub fn underline_style(mut self, style: UnderlineStyle) -> StyledObject<D> {
+        self.object_style = self.object_style.underline_style(style);
+        self
+    }
+
+    /// Set the underline color of the styled object to the passed `Color`.
+    ///
+    /// The color is written in the colon form, see `ObjectStyle::underline_color` for why.
+    pub fn underline_color(mut self, color: Color) -> StyledObject<D> {
+        self.object_style = self.object_style.underline_color(color);
         self
     }
 
     /// Increase the font intensity.
-    #[cfg(unix)]
     #[inline(always)]
     pub fn bold(self) -> StyledObject<D> {
         self.attr(Attribute::Bold)
     }
 
     /// Faint (decreased intensity) (Not widely supported).
-    #[cfg(unix)]
     #[inline(always)]
     pub fn dim(self) -> StyledObject<D> {
         self.attr(Attribute::Dim)
     }
 
     /// Make the font italic (Not widely supported; Sometimes treated as inverse).
-    #[cfg(unix)]
     #[inline(always)]
     pub fn italic(self) -> StyledObject<D> {
         self.attr(Attribute::Italic)
     }
 
     /// Underline font.
-    #[cfg(unix)]
     #[inline(always)]
     pub fn underlined(self) -> StyledObject<D> {
         self.attr(Attribute::Underlined)
     }
 
     /// Slow Blink (less than 150 per minute; not widely supported).
-    #[cfg(unix)]
     #[inline(always)]
     pub fn slow_blink(self) -> StyledObject<D> {
         self.attr(Attribute::SlowBlink)
     }
 
     /// Rapid Blink (MS-DOS ANSI.SYS; 150+ per minute; not widely supported).
-    #[cfg(unix)]
     #[inline(always)]
     pub fn rapid_blink(self) -> StyledObject<D> {
         self.attr(Attribute::RapidBlink)
     }
 
     /// Swap foreground and background colors.
-    #[cfg(unix)]
     #[inline(always)]
     pub fn reverse(self) -> StyledObject<D> {
         self.attr(Attribute::Reverse)
     }
 
     /// Hide text (Not widely supported).
-    #[cfg(unix)]
     #[inline(always)]
     pub fn hidden(self) -> StyledObject<D> {
         self.attr(Attribute::Hidden)
     }
 
     /// Characters legible, but marked for deletion. Not widely supported.
-    #[cfg(unix)]
     #[inline(always)]
     pub fn crossed_out(self) -> StyledObject<D> {
         self.attr(Attribute::CrossedOut)
     }
+
+    color_methods!(pub StyledObject<D>);
 }
 
 /// This is used to make StyledObject able to be displayed.
-/// This macro will set the styles stored in Styled Object
+/// This macro writes the colors and attributes stored in the styled object as `ANSI escape codes` into the formatter,
+/// so the styled object can also be formatted into an `String`.
 macro_rules! impl_fmt {
     ($name:ident) => {
         impl<D: fmt::$name> fmt::$name for StyledObject<D> {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let mut colored_terminal = super::super::color();
-                let mut reset = true;
+                if !style_policy().is_enabled() {
+                    return fmt::$name::fmt(&self.content, f);
+                }
+
+                #[cfg(windows)]
+                {
+                    if !ansi_support::try_enable_ansi_support() {
+                        return fmt_winapi(&self.object_style, f, |f| {
+                            fmt::$name::fmt(&self.content, f)
+                        });
+                    }
+                }
+
+                let mut reset = false;
+
+                // an reset goes first, so it does not reset the colors of the style itself.
+                if self.object_style.attrs.contains(&Attribute::Reset) {
+                    f.write_str(csi!("0m"))?;
+                    reset = true;
+                }
 
                 if let Some(bg) = self.object_style.bg_color {
-                    colored_terminal.set_bg(bg);
+                    write!(
+                        f,
+                        csi!("{}m"),
+                        AnsiColor.color_value(bg, ColorType::Background)
+                    )?;
                     reset = true;
                 }
                 if let Some(fg) = self.object_style.fg_color {
-                    colored_terminal.set_fg(fg);
+                    write!(
+                        f,
+                        csi!("{}m"),
+                        AnsiColor.color_value(fg, ColorType::Foreground)
+                    )?;
                     reset = true;
                 }
 
-                #[cfg(unix)]
-                for attr in self.object_style.attrs.iter() {
-                    write!(f, csi!("{}m"), *attr as i16);
+                for attr in self
+                    .object_style
+                    .attrs
+                    .iter()
+                    .filter(|attr| **attr != Attribute::Reset)
+                {
+                    write!(f, csi!("{}m"), *attr as i16)?;
+                    reset = true;
+                }
+
+                if let Some(style) = self.object_style.underline_style {
+                    f.write_str(&AnsiColor.underline_style_codes(style))?;
+                    reset = true;
+                }
+                if let Some(color) = self.object_style.underline_color {
+                    f.write_str(&AnsiColor.underline_color_codes(color))?;
                     reset = true;
                 }
 
                 fmt::$name::fmt(&self.content, f)?;
-                std::io::stdout().flush().expect("Flush stdout failed");
 
                 if reset {
-                    colored_terminal.reset();
+                    f.write_str(csi!("0m"))?;
                 }
 
                 Ok(())
@@ -186,3 +313,34 @@ macro_rules! impl_fmt {
 
 impl_fmt!(Debug);
 impl_fmt!(Display);
+
+/// Format the content with the colors set by the `WINAPI`, for windows consoles that do not support `ANSI escape codes`.
+///
+/// The console colors apply to the text when it is written to the console, so this only works when the formatter writes to stdout.
+#[cfg(windows)]
+fn fmt_winapi<F>(style: &ObjectStyle, f: &mut fmt::Formatter, content: F) -> fmt::Result
+where
+    F: FnOnce(&mut fmt::Formatter) -> fmt::Result,
+{
+    let mut colored_terminal = super::super::color();
+    let mut reset = false;
+
+    // The colors are set by writing to stdout, those errors can only be reported as an formatting error.
+    if let Some(bg) = style.bg_color {
+        colored_terminal.set_bg(bg).map_err(|_| fmt::Error)?;
+        reset = true;
+    }
+    if let Some(fg) = style.fg_color {
+        colored_terminal.set_fg(fg).map_err(|_| fmt::Error)?;
+        reset = true;
+    }
+
+    content(f)?;
+    io::stdout().flush().map_err(|_| fmt::Error)?;
+
+    if reset {
+        colored_terminal.reset().map_err(|_| fmt::Error)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! This module contains the `Stylize` trait, which styles text without wrapping it with `paint()` first.
+
+use style::{paint, Attribute, Color, StyledObject};
+
+use std::fmt::Display;
+
+/// Trait that styles any displayable value, like `&str` and `String`, directly.
+///
+/// The methods return an `StyledObject`, so they can be chained.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{set_style_policy, Color, StylePolicy, Stylize};
+///
+/// set_style_policy(StylePolicy::Always);
+///
+/// println!("{}", "error".red().on_blue().bold());
+/// println!("{}", format!("{} files", 3).with(Color::AnsiValue(208)));
+///
+/// assert_eq!("ok".green().to_string(), "\x1B[38;5;10mok\x1B[0m");
+/// ```
+pub trait Stylize: Sized {
+    /// Wrap the value in an `StyledObject` without any style.
+    fn stylize(self) -> StyledObject<Self>;
+
+    /// Set the foreground color to the passed `Color`.
+    fn with(self, color: Color) -> StyledObject<Self> {
+        self.stylize().with(color)
+    }
+
+    /// Set the background color to the passed `Color`.
+    fn on(self, color: Color) -> StyledObject<Self> {
+        self.stylize().on(color)
+    }
+
+    /// Apply the passed `Attribute`.
+    fn attr(self, attr: Attribute) -> StyledObject<Self> {
+        self.stylize().attr(attr)
+    }
+
+    /// Increase the font intensity.
+    fn bold(self) -> StyledObject<Self> {
+        self.attr(Attribute::Bold)
+    }
+
+    /// Faint (decreased intensity) (Not widely supported).
+    fn dim(self) -> StyledObject<Self> {
+        self.attr(Attribute::Dim)
+    }
+
+    /// Make the font italic (Not widely supported; Sometimes treated as inverse).
+    fn italic(self) -> StyledObject<Self> {
+        self.attr(Attribute::Italic)
+    }
+
+    /// Underline font.
+    fn underlined(self) -> StyledObject<Self> {
+        self.attr(Attribute::Underlined)
+    }
+
+    /// Slow Blink (less than 150 per minute; not widely supported).
+    fn slow_blink(self) -> StyledObject<Self> {
+        self.attr(Attribute::SlowBlink)
+    }
+
+    /// Rapid Blink (MS-DOS ANSI.SYS; 150+ per minute; not widely supported).
+    fn rapid_blink(self) -> StyledObject<Self> {
+        self.attr(Attribute::RapidBlink)
+    }
+
+    /// Swap foreground and background colors.
+    fn reverse(self) -> StyledObject<Self> {
+        self.attr(Attribute::Reverse)
+    }
+
+    /// Hide text (Not widely supported).
+    fn hidden(self) -> StyledObject<Self> {
+        self.attr(Attribute::Hidden)
+    }
+
+    /// Characters legible, but marked for deletion. Not widely supported.
+    fn crossed_out(self) -> StyledObject<Self> {
+        self.attr(Attribute::CrossedOut)
+    }
+
+    color_methods!(StyledObject<Self>);
+}
+
+/// An `StyledObject` has its own style methods, so chained calls keep styling the same object.
+impl<D: Display> Stylize for D {
+    fn stylize(self) -> StyledObject<D> {
+        paint(self)
+    }
+}
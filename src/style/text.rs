@@ -0,0 +1,243 @@
+//! This module contains functions to work with text that contains escape sequences, like the output of an `StyledObject`.
+//!
+//! The escape sequences take no space on the screen, so they are not counted when the width of the text is measured.
+//! The width of the characters is their display width, an wide character like `界` takes two columns and an combining
+//! character takes none.
+
+use unicode_width::UnicodeWidthChar;
+
+/// An part of an text, either an escape sequence or an character.
+enum Segment<'a> {
+    Escape(&'a str),
+    Char(char),
+}
+
+/// Split the given text into escape sequences and characters.
+///
+/// An control sequence (`ESC [`) ends with an byte in the range `@` to `~`, an operating system command (`ESC ]`)
+/// with `BEL` or `ESC \` and other escape sequences are two characters.
+fn segments(text: &str) -> Vec<Segment<'_>> {
+    let bytes = text.as_bytes();
+    let mut segments = Vec::new();
+    let mut index = 0;
+
+    while index < text.len() {
+        if bytes[index] != 0x1B {
+            let ch = text[index..].chars().next().unwrap_or('\u{FFFD}');
+            segments.push(Segment::Char(ch));
+            index += ch.len_utf8();
+            continue;
+        }
+
+        let len = match bytes.get(index + 1) {
+            Some(&b'[') => bytes[index + 2..]
+                .iter()
+                .position(|byte| (0x40..=0x7E).contains(byte))
+                .map(|end| end + 3),
+            Some(&b']') => {
+                let rest = &bytes[index + 2..];
+                rest.iter()
+                    .enumerate()
+                    .find(|&(i, &byte)| {
+                        byte == 0x07 || (byte == 0x1B && rest.get(i + 1) == Some(&b'\\'))
+                    })
+                    .map(|(end, &byte)| if byte == 0x07 { end + 3 } else { end + 4 })
+            }
+            Some(&byte) if byte < 0x80 => Some(2),
+            _ => Some(1),
+        };
+
+        // an sequence that is cut off takes the rest of the text.
+        let end = len.map(|len| index + len).unwrap_or(text.len());
+        segments.push(Segment::Escape(&text[index..end]));
+        index = end;
+    }
+
+    segments
+}
+
+/// Remove all escape sequences from the given text.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::strip_ansi_codes;
+///
+/// assert_eq!(strip_ansi_codes("\x1B[38;5;9mred\x1B[0m text"), "red text");
+/// ```
+pub fn strip_ansi_codes(text: &str) -> String {
+    segments(text)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Char(ch) => Some(ch),
+            Segment::Escape(_) => None,
+        })
+        .collect()
+}
+
+/// Get the number of columns the given text takes on the screen, the escape sequences and control characters are not counted.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::display_width;
+///
+/// assert_eq!(display_width("\x1B[1mbold\x1B[0m"), 4);
+/// assert_eq!(display_width("世界"), 4);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    segments(text)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Char(ch) => ch.width().unwrap_or(0),
+            Segment::Escape(_) => 0,
+        })
+        .sum()
+}
+
+/// Cut the given text off so that it takes at most the given number of columns.
+///
+/// The escape sequences are kept, also the ones after the cut, so an color reset at the end of the text is not lost.
+/// An wide character that does not fit completely is left out.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::truncate;
+///
+/// assert_eq!(truncate("\x1B[31mHello world\x1B[0m", 5), "\x1B[31mHello\x1B[0m");
+/// ```
+pub fn truncate(text: &str, width: usize) -> String {
+    let mut truncated = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut full = false;
+
+    for segment in segments(text) {
+        match segment {
+            Segment::Escape(sequence) => truncated.push_str(sequence),
+            Segment::Char(ch) => {
+                let ch_width = ch.width().unwrap_or(0);
+
+                if full || used + ch_width > width {
+                    full = true;
+                    continue;
+                }
+
+                used += ch_width;
+                truncated.push(ch);
+            }
+        }
+    }
+
+    truncated
+}
+
+/// Add spaces to the end of the given text until it takes the given number of columns,
+/// the text is not changed when it is already that wide.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::pad;
+///
+/// assert_eq!(pad("\x1B[1mab\x1B[0m", 4), "\x1B[1mab\x1B[0m  ");
+/// ```
+pub fn pad(text: &str, width: usize) -> String {
+    let mut padded = text.to_string();
+
+    for _ in display_width(text)..width {
+        padded.push(' ');
+    }
+
+    padded
+}
+
+/// Truncate or pad the given text so that it takes exactly the given number of columns, for example for an table column.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{display_width, fit, paint, Color};
+///
+/// let cell = fit(&paint("Description").with(Color::Red).to_string(), 8);
+/// assert_eq!(display_width(&cell), 8);
+/// ```
+pub fn fit(text: &str, width: usize) -> String {
+    pad(&truncate(text, width), width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_width, strip_ansi_codes, truncate};
+
+    #[test]
+    fn strips_control_sequences() {
+        assert_eq!(strip_ansi_codes("\x1B[1;38;2;1;2;3mab\x1B[0mc"), "abc");
+        assert_eq!(strip_ansi_codes("\x1B[?25la"), "a");
+    }
+
+    #[test]
+    fn strips_operating_system_commands() {
+        assert_eq!(strip_ansi_codes("\x1B]0;title\x07a"), "a");
+        assert_eq!(strip_ansi_codes("\x1B]8;;link\x1B\\a\x1B]8;;\x1B\\"), "a");
+    }
+
+    #[test]
+    fn strips_two_character_escape_sequences() {
+        assert_eq!(strip_ansi_codes("\x1B7a\x1B8"), "a");
+    }
+
+    #[test]
+    fn strips_an_escape_sequence_that_is_cut_off() {
+        assert_eq!(strip_ansi_codes("a\x1B[38;5"), "a");
+        assert_eq!(strip_ansi_codes("a\x1B]0;title"), "a");
+        assert_eq!(strip_ansi_codes("a\x1B"), "a");
+    }
+
+    #[test]
+    fn measures_the_display_width() {
+        assert_eq!(display_width(""), 0);
+        assert_eq!(display_width("\x1B[31mab\x1B[0m"), 2);
+        assert_eq!(display_width("a\u{754C}"), 3);
+        // an combining character and an control character take no columns.
+        assert_eq!(display_width("e\u{301}\t"), 1);
+    }
+
+    #[test]
+    fn truncates_at_the_given_width() {
+        assert_eq!(truncate("abc", 5), "abc");
+        assert_eq!(truncate("abc", 2), "ab");
+        assert_eq!(truncate("abc", 0), "");
+    }
+
+    #[test]
+    fn keeps_the_escape_sequences_when_truncating() {
+        assert_eq!(
+            truncate("\x1B[1mab\x1B[31mcd\x1B[0m", 1),
+            "\x1B[1ma\x1B[31m\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn leaves_out_an_wide_character_that_does_not_fit() {
+        assert_eq!(truncate("a\u{754C}b", 2), "a");
+        assert_eq!(truncate("a\u{754C}b", 3), "a\u{754C}");
+        // the narrow character after it is not written in the remaining column either.
+        assert_eq!(truncate("\u{754C}\u{754C}ab", 3), "\u{754C}");
+    }
+
+    #[test]
+    fn keeps_combining_characters_when_truncating() {
+        assert_eq!(truncate("e\u{301}x", 1), "e\u{301}");
+    }
+}
@@ -18,43 +18,29 @@ impl Construct for AnsiTerminal {
 }
 
 impl ITerminal for AnsiTerminal {
-    fn clear(&self, clear_type: ClearType) {
-        let mut some_writer = io::stdout();
+    fn clear(&self, output: &mut dyn Write, clear_type: ClearType) -> io::Result<()> {
         match clear_type {
-            ClearType::All => {
-                write!(&mut some_writer, csi!("2J"));
-            }
-            ClearType::FromCursorDown => {
-                write!(&mut some_writer, csi!("J"));
-            }
-            ClearType::FromCursorUp => {
-                write!(&mut some_writer, csi!("1J"));
-            }
-            ClearType::CurrentLine => {
-                write!(&mut some_writer, csi!("2K"));
-            }
-            ClearType::UntilNewLine => {
-                write!(&mut some_writer, csi!("K"));
-            }
-        };
+            ClearType::All => write!(output, csi!("2J")),
+            ClearType::FromCursorDown => write!(output, csi!("J")),
+            ClearType::FromCursorUp => write!(output, csi!("1J")),
+            ClearType::CurrentLine => write!(output, csi!("2K")),
+            ClearType::UntilNewLine => write!(output, csi!("K")),
+        }
     }
 
-    fn terminal_size(&self) -> (u16, u16) {
+    fn terminal_size(&self) -> io::Result<(u16, u16)> {
         functions::get_terminal_size()
     }
 
-    fn scroll_up(&self, count: i16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("{}S"), count);
+    fn scroll_up(&self, output: &mut dyn Write, count: i16) -> io::Result<()> {
+        write!(output, csi!("{}S"), count)
     }
 
-    fn scroll_down(&self, count: i16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("{}T"), count);
+    fn scroll_down(&self, output: &mut dyn Write, count: i16) -> io::Result<()> {
+        write!(output, csi!("{}T"), count)
     }
 
-    fn set_size(&self, width: i16, height: i16) {
-        let mut some_writer = io::stdout();
-        write!(&mut some_writer, csi!("8;{};{}t"), width, height);
+    fn set_size(&self, output: &mut dyn Write, width: i16, height: i16) -> io::Result<()> {
+        write!(output, csi!("8;{};{}t"), width, height)
     }
 }
@@ -0,0 +1,251 @@
+//! This module contains the `Buffer`, an grid of cells that can be drawn to and rendered by the `Screen`.
+//!
+//! Every cell holds one character with the style it is written with.
+//! An wide character like `界` takes two cells, the second cell is an continuation cell that is covered by the character.
+//! The `Screen` keeps the buffer of the previous frame, so only the cells that changed since then are written to the terminal.
+
+use style::ObjectStyle;
+use unicode_width::UnicodeWidthChar;
+
+/// One cell of an `Buffer`, the character and the style it is written with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: ObjectStyle,
+    /// Whether this cell is the second cell of the wide character in the cell before it, it is not written itself.
+    pub continuation: bool,
+}
+
+impl Cell {
+    /// Create an cell with the given character and style.
+    pub fn new(ch: char, style: ObjectStyle) -> Cell {
+        Cell {
+            ch,
+            style,
+            continuation: false,
+        }
+    }
+
+    /// Create the second cell of an wide character with the given style.
+    pub fn continuation(style: ObjectStyle) -> Cell {
+        Cell {
+            ch: ' ',
+            style,
+            continuation: true,
+        }
+    }
+}
+
+/// Get the number of cells the given character takes, 0 for control characters and combining characters.
+pub fn char_width(ch: char) -> u16 {
+    ch.width().unwrap_or(0) as u16
+}
+
+impl Default for Cell {
+    /// An empty cell, an space without any style.
+    fn default() -> Cell {
+        Cell::new(' ', ObjectStyle::new())
+    }
+}
+
+/// An grid of cells with an fixed width and height, the positions are 0-based like the cursor positions.
+///
+/// Writing outside of the buffer is ignored, so text is clipped at the edges of the buffer.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{Color, ObjectStyle};
+/// use self::crossterm::terminal::buffer::Buffer;
+///
+/// let previous = Buffer::new(10, 2);
+/// let mut buffer = Buffer::new(10, 2);
+/// buffer.set_str(2, 1, "Hi", &ObjectStyle::new().fg(Color::Red));
+///
+/// let changes = buffer.diff(&previous);
+/// assert_eq!(changes.len(), 2);
+/// assert_eq!((changes[0].0, changes[0].1, changes[0].2.ch), (2, 1, 'H'));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// Create an buffer of the given size filled with empty cells.
+    pub fn new(width: u16, height: u16) -> Buffer {
+        Buffer {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    /// Get the number of columns of this buffer.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Get the number of rows of this buffer.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Get the cell at the given position, `None` when the position is outside of the buffer.
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.index_of(x, y).map(|index| &self.cells[index])
+    }
+
+    /// Get the cell at the given position mutable, `None` when the position is outside of the buffer.
+    pub fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        match self.index_of(x, y) {
+            Some(index) => Some(&mut self.cells[index]),
+            None => None,
+        }
+    }
+
+    /// Set the cell at the given position, nothing happens when the position is outside of the buffer.
+    ///
+    /// The cell is set as it is, use `set_char()` to write an character that may be wide.
+    pub fn set(&mut self, x: u16, y: u16, cell: Cell) {
+        if let Some(index) = self.index_of(x, y) {
+            self.cells[index] = cell;
+        }
+    }
+
+    /// Write the given character with the given style at the given position, an wide character also takes the next cell.
+    ///
+    /// An wide character that does not fit on the row, an control character and an combining character are not written.
+    /// When an half of an wide character is overwritten the other half is cleared.
+    pub fn set_char(&mut self, x: u16, y: u16, ch: char, style: &ObjectStyle) {
+        let width = char_width(ch);
+
+        if width == 0 || x as usize + width as usize > self.width as usize || y >= self.height {
+            return;
+        }
+
+        for column in x..x + width {
+            self.clear_wide_char(column, y);
+        }
+
+        self.set(x, y, Cell::new(ch, style.clone()));
+        for column in x + 1..x + width {
+            self.set(column, y, Cell::continuation(style.clone()));
+        }
+    }
+
+    /// Write the given text with the given style starting at the given position, every character takes as many cells as it is wide.
+    ///
+    /// The text does not wrap, the characters that do not fit on the row are not written.
+    /// The combining characters are left out because an cell holds one character.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::ObjectStyle;
+    /// use self::crossterm::terminal::buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::new(5, 1);
+    /// buffer.set_str(0, 0, "a界b", &ObjectStyle::new());
+    ///
+    /// assert_eq!(buffer.get(1, 0).unwrap().ch, '界');
+    /// assert!(buffer.get(2, 0).unwrap().continuation);
+    /// assert_eq!(buffer.get(3, 0).unwrap().ch, 'b');
+    /// ```
+    pub fn set_str(&mut self, x: u16, y: u16, text: &str, style: &ObjectStyle) {
+        let mut column = x as usize;
+
+        for ch in text.chars() {
+            let width = char_width(ch) as usize;
+
+            if column + width > self.width as usize {
+                break;
+            }
+
+            self.set_char(column as u16, y, ch, style);
+            column += width;
+        }
+    }
+
+    /// Set all cells to the given cell.
+    pub fn fill(&mut self, cell: &Cell) {
+        for target in self.cells.iter_mut() {
+            target.clone_from(cell);
+        }
+    }
+
+    /// Set all cells to empty cells.
+    pub fn clear(&mut self) {
+        self.fill(&Cell::default());
+    }
+
+    /// Change the size of this buffer, the cells that are inside both the old and new size are kept.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let mut resized = Buffer::new(width, height);
+
+        for y in 0..self.height.min(height) {
+            for x in 0..self.width.min(width) {
+                if let Some(cell) = self.get(x, y) {
+                    resized.set(x, y, cell.clone());
+                }
+            }
+
+            // an wide character that is cut in half at the new last column is removed.
+            let last = width.saturating_sub(1);
+            let cut = width < self.width
+                && resized
+                    .get(last, y)
+                    .is_some_and(|cell| char_width(cell.ch) > 1);
+            if cut {
+                resized.set(last, y, Cell::default());
+            }
+        }
+
+        *self = resized;
+    }
+
+    /// Get the position and cell of every cell that is different in the given previous buffer, ordered by row and column.
+    ///
+    /// When the buffers have a different size every cell is returned.
+    pub fn diff<'a>(&'a self, previous: &Buffer) -> Vec<(u16, u16, &'a Cell)> {
+        let same_size = self.width == previous.width && self.height == previous.height;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|&(index, cell)| !same_size || previous.cells[index] != *cell)
+            .map(|(index, cell)| {
+                let x = (index % self.width as usize) as u16;
+                let y = (index / self.width as usize) as u16;
+                (x, y, cell)
+            })
+            .collect()
+    }
+
+    /// Clear the other half of the wide character that takes the cell at the given position, so no half character is left when that cell is overwritten.
+    fn clear_wide_char(&mut self, x: u16, y: u16) {
+        let continuation = self.get(x, y).is_some_and(|cell| cell.continuation);
+
+        if continuation && x > 0 {
+            self.set(x - 1, y, Cell::default());
+        }
+
+        if self.get(x + 1, y).is_some_and(|cell| cell.continuation) {
+            self.set(x + 1, y, Cell::default());
+        }
+    }
+
+    fn index_of(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+}
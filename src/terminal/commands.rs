@@ -0,0 +1,69 @@
+//! This module contains the terminal commands that can be used with the `queue!` and `execute!` macros.
+
+use super::AnsiTerminal;
+#[cfg(target_os = "windows")]
+use super::WinApiTerminal;
+use super::{ClearType, ITerminal};
+use QueueableCommand;
+
+use std::io::{self, Write};
+
+/// Command that clears the terminal with the given `ClearType`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Clear(pub ClearType);
+
+impl QueueableCommand for Clear {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiTerminal.clear(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiTerminal.clear(output, self.0)
+    }
+}
+
+/// Command that scrolls the terminal the given number of lines up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ScrollUp(pub i16);
+
+impl QueueableCommand for ScrollUp {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiTerminal.scroll_up(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiTerminal.scroll_up(output, self.0)
+    }
+}
+
+/// Command that scrolls the terminal the given number of lines down.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ScrollDown(pub i16);
+
+impl QueueableCommand for ScrollDown {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiTerminal.scroll_down(output, self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiTerminal.scroll_down(output, self.0)
+    }
+}
+
+/// Command that resizes the terminal to the given width and height.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SetSize(pub i16, pub i16);
+
+impl QueueableCommand for SetSize {
+    fn write_ansi(&self, output: &mut dyn Write) -> io::Result<()> {
+        AnsiTerminal.set_size(output, self.0, self.1)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self, output: &mut dyn Write) -> io::Result<()> {
+        WinApiTerminal.set_size(output, self.0, self.1)
+    }
+}
@@ -4,23 +4,41 @@
 //! - alternate screen
 //! - raw mode
 //! - clearing resizing scrolling the terminal.
+//! - getting notified when the terminal is resized.
 
+#[allow(clippy::module_inception)]
 mod terminal;
 
 mod ansi_terminal;
+mod commands;
 #[cfg(target_os = "windows")]
 mod winapi_terminal;
 
-mod raw;
-mod screen;
+pub mod buffer;
+pub mod raw;
+#[cfg(unix)]
+mod resize;
+pub mod screen;
+pub mod virtual_terminal;
 
 use self::ansi_terminal::AnsiTerminal;
 #[cfg(target_os = "windows")]
 use self::winapi_terminal::WinApiTerminal;
 
+pub use self::commands::{Clear, ScrollDown, ScrollUp, SetSize};
+#[cfg(unix)]
+pub use self::resize::{on_resize, ResizeListener};
 pub use self::terminal::{terminal, Terminal};
 
+use std::io::{self, Write};
+
 /// Enum that can be used for the kind of clearing that can be done in the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum ClearType {
     All,
     FromCursorDown,
@@ -29,23 +47,26 @@ pub enum ClearType {
     UntilNewLine,
 }
 
-///! This trait defines the actions that can be preformed with the terminal.
-///! This trait can be implemented so that an concrete implementation of the ITerminal can forfill
-///! the wishes to work on an specific platform.
-///!
-///! ## For example:
-///!
-///! This trait is implemented for `WINAPI` (Windows specific) and `ANSI` (Unix specific),
-///! so that cursor related actions can be preformed on both unix and windows systems.
+/// This trait defines the actions that can be preformed with the terminal.
+/// This trait can be implemented so that an concrete implementation of the ITerminal can forfill
+/// the wishes to work on an specific platform.
+///
+/// ## For example:
+///
+/// This trait is implemented for `WINAPI` (Windows specific) and `ANSI` (Unix specific),
+/// so that cursor related actions can be preformed on both unix and windows systems.
+///
+/// The actions are written to the given output, implementations that do not write to the output flush it first
+/// so that everything that is written before the action is shown before it is performed.
 pub trait ITerminal {
     /// Clear the current cursor by specifying the clear type
-    fn clear(&self, clear_type: ClearType);
+    fn clear(&self, output: &mut dyn Write, clear_type: ClearType) -> io::Result<()>;
     /// Get the terminal size (x,y)
-    fn terminal_size(&self) -> (u16, u16);
+    fn terminal_size(&self) -> io::Result<(u16, u16)>;
     /// Scroll `n` lines up in the current terminal.
-    fn scroll_up(&self, count: i16);
+    fn scroll_up(&self, output: &mut dyn Write, count: i16) -> io::Result<()>;
     /// Scroll `n` lines down in the current terminal.
-    fn scroll_down(&self, count: i16);
+    fn scroll_down(&self, output: &mut dyn Write, count: i16) -> io::Result<()>;
     /// Resize terminal to the given width and height.
-    fn set_size(&self, width: i16, height: i16);
+    fn set_size(&self, output: &mut dyn Write, width: i16, height: i16) -> io::Result<()>;
 }
@@ -2,8 +2,8 @@
 //!
 //! What exactly is raw state:
 //! - No line buffering.
-//!    Normally the terminals uses line buffering. This means that the input will be send to the terminal line by line.
-//!    With raw mode the input will be send one byte at a time.
+//!   Normally the terminals uses line buffering. This means that the input will be send to the terminal line by line.
+//!   With raw mode the input will be send one byte at a time.
 //! - Input
 //!   All input has to be written manually by the programmer.
 //! - Characters
@@ -14,9 +14,20 @@
 //!
 //! # Example
 //!
-//! ```rust
-//! to be implemented
+//! ```rust,no_run
+//! extern crate crossterm;
 //!
+//! use self::crossterm::terminal::raw::IntoRawMode;
+//! use self::crossterm::Context;
+//! use std::io::{stdout, Write};
+//!
+//! let mut context = Context::new();
+//!
+//! {
+//!     // Switch to raw mode, raw mode will be disabled when `raw` goes out of scope.
+//!     let mut raw = stdout().into_raw_mode(&mut context).unwrap();
+//!     write!(raw, "Some text in raw mode.").unwrap();
+//! }
 //! ```
 
 #[cfg(not(windows))]
@@ -24,8 +35,9 @@ use state::commands::unix_command::EnableRawModeCommand;
 #[cfg(windows)]
 use state::commands::win_commands::EnableRawModeCommand;
 
+use state;
 use state::commands::IContextCommand;
-use Context;
+use {Context, StateKey, TerminalOutput};
 
 use std::io::{self, Write};
 
@@ -33,11 +45,13 @@ use std::io::{self, Write};
 pub struct RawTerminal<'a, W: Write> {
     output: W,
     context: &'a mut Context,
+    /// The key of the raw mode change in the context.
+    key: StateKey,
 }
 
 /// Trait withs contains a method for switching into raw mode.
 pub trait IntoRawMode: Write + Sized {
-    fn into_raw_mode<'a>(self, context: &'a mut Context) -> io::Result<RawTerminal<Self>>;
+    fn into_raw_mode(self, context: &mut Context) -> io::Result<RawTerminal<'_, Self>>;
 }
 
 impl<W: Write> IntoRawMode for W {
@@ -46,18 +60,26 @@ impl<W: Write> IntoRawMode for W {
     /// Raw mode means that input (stdin) won't be printed it will instead have to be written manually by
     /// the program. The input isn't canonicalised or line buffered (that is, you can
     /// read from input(stdin) one byte of a time).
-    fn into_raw_mode<'a>(self, context: &'a mut Context) -> io::Result<RawTerminal<Self>> {
-        let (mut command, _) = EnableRawModeCommand::new(context);
-        let success = command.execute();
+    ///
+    /// An error is returned when raw mode can not be enabled, for example when the input is not a terminal,
+    /// or when raw mode is already enabled because disabling it twice would not bring the terminal back in its original mode.
+    fn into_raw_mode(self, context: &mut Context) -> io::Result<RawTerminal<'_, Self>> {
+        state::claim_raw_mode()?;
 
-        if success {
-            Ok(RawTerminal {
-                output: self,
-                context: context,
-            })
-        } else {
-            panic!("cannot move into raw mode")
+        let (mut command, key) = EnableRawModeCommand::new(context);
+
+        if let Err(e) = command.execute() {
+            // raw mode is not enabled, so it should not be disabled when the context is restored.
+            let _ = context.undo_state(key);
+            state::set_raw_mode(false);
+            return Err(e);
         }
+
+        Ok(RawTerminal {
+            output: self,
+            context,
+            key,
+        })
     }
 }
 
@@ -71,9 +93,20 @@ impl<'a, W: Write> Write for RawTerminal<'a, W> {
     }
 }
 
-/// If an instance of `RawTerminal` will be dropped all terminal changes that are made will be undone.
+impl<'a, W: Write + TerminalOutput> TerminalOutput for RawTerminal<'a, W> {
+    fn is_tty(&self) -> bool {
+        self.output.is_tty()
+    }
+
+    fn is_stdout(&self) -> bool {
+        self.output.is_stdout()
+    }
+}
+
+/// If an instance of `RawTerminal` will be dropped raw mode is disabled, the other changes in the context are left alone.
 impl<'a, W: Write> Drop for RawTerminal<'a, W> {
     fn drop(&mut self) {
-        self.context.restore_changes();
+        // there is no way to report an error from here.
+        let _ = self.context.undo_state(self.key);
     }
 }
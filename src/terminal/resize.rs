@@ -0,0 +1,55 @@
+//! This module is used for getting notified when the terminal is resized.
+//!
+//! Unix terminals send the `SIGWINCH` signal to the process when they are resized.
+//! If you read the input with `TerminalInput::read()` you will also get an `Event::Resize` instead.
+
+use kernel::unix_kernel::{signal, terminal};
+use libc::SIGWINCH;
+
+use std::io;
+
+/// Struct that calls the callback given to `on_resize()` each time the terminal is resized.
+///
+/// The callback is no longer called when the listener is dropped.
+pub struct ResizeListener {
+    id: usize,
+}
+
+impl Drop for ResizeListener {
+    fn drop(&mut self) {
+        signal::unregister(self.id);
+    }
+}
+
+/// Call the given callback with the new terminal size (the same as `Terminal::terminal_size()` returns) each time the terminal is resized.
+///
+/// The callback is called on an background thread and not inside the signal handler, so it can do anything.
+///
+/// #Example
+///
+/// ```rust,no_run
+///
+/// extern crate crossterm;
+/// use crossterm::terminal;
+///
+/// let listener = terminal::on_resize(|width, height| {
+///     println!("The terminal is resized to: {}, {}", width, height);
+/// }).unwrap();
+///
+/// // the callback is called until the listener is dropped.
+/// drop(listener);
+///
+/// ```
+pub fn on_resize<F>(callback: F) -> io::Result<ResizeListener>
+where
+    F: Fn(u16, u16) + Send + Sync + 'static,
+{
+    let id = signal::register(SIGWINCH, move || {
+        // the size can not be read when the output is not a terminal anymore, then there is nothing to report.
+        if let Ok((width, height)) = terminal::terminal_size() {
+            callback(width, height);
+        }
+    })?;
+
+    Ok(ResizeListener { id })
+}
@@ -1,28 +1,66 @@
 //! This module contains all the logic for switching between alternate screen and main screen.
+//!
+//! The switch is written as `ANSI escape code` to the output the `ToAlternateScreen` and `ToMainScreen` are written to.
+//! Windows consoles that do not support `ANSI escape codes` switch with the `WINAPI` instead.
+//!
+//! The `Screen` draws an `Buffer` on the alternate screen and only writes the cells that changed since the last frame.
 
+#[cfg(target_os = "windows")]
+use kernel::windows_kernel::ansi_support;
+#[cfg(target_os = "windows")]
+use state::commands::{win_commands, ICommand};
+
+use super::buffer::{self, Buffer};
+use super::{Clear, ClearType};
+use cursor::Goto;
 use shared::functions;
-use state::commands::*;
-use Context;
+use state::commands::{AlternateScreenCommand, IContextCommand};
+use state::{self, StateKey};
+use style::{
+    Attribute, ObjectStyle, ResetColor, SetAttr, SetBg, SetFg, SetUnderlineColor, SetUnderlineStyle,
+};
+use {Print, TerminalOutput};
 
 use std::io::{self, Write};
 use std::{fmt, ops};
 
+/// Struct that switches back to the main screen buffer on display.
+///
+/// Writing it yourself is not tracked, the process only knows about the switches of an `AlternateScreen`.
 pub struct ToMainScreen;
 
 impl fmt::Display for ToMainScreen {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        get_to_alternate_screen_command().undo();
-        Ok(())
+        #[cfg(target_os = "windows")]
+        {
+            if !ansi_support::try_enable_ansi_support() {
+                return win_commands::ToAlternateScreenBufferCommand::new()
+                    .undo()
+                    .map_err(|_| fmt::Error);
+            }
+        }
+
+        f.write_str(csi!("?1049l"))
     }
 }
 
 /// Struct that switches to alternate screen buffer on display.
+///
+/// Writing it yourself is not tracked, the process only knows about the switches of an `AlternateScreen`.
 pub struct ToAlternateScreen;
 
 impl fmt::Display for ToAlternateScreen {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        get_to_alternate_screen_command().execute();
-        Ok(())
+        #[cfg(target_os = "windows")]
+        {
+            if !ansi_support::try_enable_ansi_support() {
+                return win_commands::ToAlternateScreenBufferCommand::new()
+                    .execute()
+                    .map_err(|_| fmt::Error);
+            }
+        }
+
+        f.write_str(csi!("?1049h"))
     }
 }
 
@@ -30,38 +68,80 @@ impl fmt::Display for ToAlternateScreen {
 ///
 /// #Example
 ///
-/// ```rust
+/// ```rust,no_run
 /// extern crate crossterm;
 /// use self::crossterm::terminal::screen;
+/// use std::io::{stdout, Write};
 /// use std::{time, thread};
-///    ...
-///
-///    // Initialize and switch to the alternate screen from an std output handle.
-///    // Now you can write to this screen.
-///    let mut screen = screen::AlternateScreen::from(stdout());
-///    // Write some text to the alternate screen.
-///    write!(screen, "Welcome to the alternate screen. Wait 4 seconds to switch back").unwrap();
-///    thread::sleep(time::Duration::from_secs(4));
-///    // switch back to main screen.
-///    write!(screen, "{}", screen::ToMainScreen);
-///    write!(screen, "{}", "We are back again at the main screen");
-///
-///    ...
 ///
+/// // Initialize and switch to the alternate screen from an std output handle.
+/// // Now you can write to this screen.
+/// let mut screen = screen::AlternateScreen::from(stdout()).unwrap();
+/// // Write some text to the alternate screen.
+/// write!(screen, "Welcome to the alternate screen. Wait 4 seconds to switch back").unwrap();
+/// thread::sleep(time::Duration::from_secs(4));
+/// // switch back to main screen.
+/// write!(screen, "{}", screen::ToMainScreen).unwrap();
+/// write!(screen, "{}", "We are back again at the main screen").unwrap();
 /// ```
 pub struct AlternateScreen<W: Write> {
     /// The output target.
     output: W,
-    context: Context,
+    /// The key of the switch in the shared context, so the terminal also leaves the alternate screen when that context is restored.
+    /// `None` when the output is no terminal, then only this screen knows it is at the alternate screen.
+    key: Option<StateKey>,
 }
 
-impl<W: Write> AlternateScreen<W> {
-    pub fn from(mut output: W) -> Self {
-        write!(output, "{}", ToAlternateScreen);
-        AlternateScreen {
-            output: output,
-            context: Context::new(),
+impl<W: Write + TerminalOutput> AlternateScreen<W> {
+    /// Switch to the alternate screen by writing to the given output, everything written to the `AlternateScreen` is written to that output.
+    ///
+    /// When the output is an terminal the switch is registered in the context shared by the whole process,
+    /// so the terminal also switches back when that context is restored, for example by an `RestoreGuard`.
+    /// Other outputs, like an `Vec<u8>` or an `VirtualTerminal`, do not change the state of the process.
+    ///
+    /// An error is returned when the terminal could not switch to the alternate screen,
+    /// or when it already is at the alternate screen because alternate screens can not be nested.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::terminal::screen::AlternateScreen;
+    /// use self::crossterm::Crossterm;
+    ///
+    /// let screen = AlternateScreen::from(Vec::new()).unwrap();
+    ///
+    /// // the switch is written to the vector, the terminal of the process is still at the main screen.
+    /// assert_eq!(&screen[..], b"\x1B[?1049h");
+    /// assert!(!Crossterm::new().is_alternate_screen_active());
+    /// assert!(Crossterm::new().context().registrations().is_empty());
+    /// ```
+    pub fn from(mut output: W) -> io::Result<Self> {
+        if !output.is_tty() {
+            write!(output, "{}", ToAlternateScreen)?;
+            output.flush()?;
+            return Ok(AlternateScreen { output, key: None });
         }
+
+        state::claim_alternate_screen()?;
+
+        let mut context = state::shared_context().clone();
+        let (_, key) = AlternateScreenCommand::new(&mut context);
+
+        let switched = write!(output, "{}", ToAlternateScreen).and_then(|_| output.flush());
+
+        if let Err(e) = switched {
+            // the terminal did not switch, so it should not switch back when the shared context is restored.
+            state::set_alternate_screen(false);
+            let _ = context.undo_state(key);
+            return Err(e);
+        }
+
+        Ok(AlternateScreen {
+            output,
+            key: Some(key),
+        })
     }
 }
 
@@ -89,22 +169,218 @@ impl<W: Write> Write for AlternateScreen<W> {
     }
 }
 
+impl<W: Write + TerminalOutput> TerminalOutput for AlternateScreen<W> {
+    fn is_tty(&self) -> bool {
+        self.output.is_tty()
+    }
+
+    fn is_stdout(&self) -> bool {
+        self.output.is_stdout()
+    }
+}
+
 impl<W: Write> Drop for AlternateScreen<W> {
     fn drop(&mut self) {
-        write!(self, "{}", ToMainScreen).expect("switch to main screen");
+        // there is no way to report an error from here, the terminal stays at the alternate screen when switching back fails.
+        let _ = write!(self.output, "{}", ToMainScreen);
+        let _ = self.output.flush();
+
+        if let Some(key) = self.key {
+            // the terminal is back at the main screen, so this only removes the switch from the shared context.
+            state::set_alternate_screen(false);
+            let _ = state::shared_context().clone().undo_state(key);
+        }
     }
 }
 
-/// Get the alternate screen command to enable and disable alternate screen based on the current platform
-fn get_to_alternate_screen_command() -> Box<ICommand> {
-    #[cfg(target_os = "windows")]
-    let command = functions::get_module::<Box<ICommand>>(
-        win_commands::ToAlternateScreenBufferCommand::new(),
-        shared_commands::ToAlternateScreenBufferCommand::new(),
-    ).unwrap();
+/// An double buffered screen on the alternate screen.
+///
+/// Everything is drawn into the `Buffer` of the screen, `render()` writes the cells that changed since the previous `render()`
+/// with as few cursor movements and style changes as possible and flushes them all at once.
+/// So there is no need to keep track of the regions that should be redrawn.
+///
+/// #Example
+///
+/// ```rust,no_run
+/// extern crate crossterm;
+///
+/// use self::crossterm::style::{Color, ObjectStyle};
+/// use self::crossterm::terminal::screen::Screen;
+/// use std::io::stdout;
+///
+/// let mut screen = Screen::new(stdout()).unwrap();
+///
+/// for frame in 0..10 {
+///     screen.buffer_mut().clear();
+///     screen.buffer_mut().set_str(frame, 1, "Moving text", &ObjectStyle::new().fg(Color::Green));
+///
+///     // only the cells that changed since the previous frame are written.
+///     screen.render().unwrap();
+/// }
+/// ```
+pub struct Screen<W: Write> {
+    output: AlternateScreen<W>,
+    buffer: Buffer,
+    /// The buffer as it is rendered to the terminal.
+    previous: Buffer,
+    /// Whether the whole screen should be redrawn on the next render.
+    redraw: bool,
+}
+
+impl<W: Write + TerminalOutput> Screen<W> {
+    /// Switch the given output to the alternate screen and create an screen with the size of the terminal.
+    pub fn new(output: W) -> io::Result<Screen<W>> {
+        // the terminal size is the position of the last column and row.
+        let (width, height) = functions::get_terminal_size()?;
+        Screen::with_size(output, width + 1, height + 1)
+    }
 
-    #[cfg(not(target_os = "windows"))]
-    let command = shared_commands::ToAlternateScreenBufferCommand::new();
+    /// Switch the given output to the alternate screen and create an screen with the given size.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::ObjectStyle;
+    /// use self::crossterm::terminal::screen::Screen;
+    ///
+    /// let mut screen = Screen::with_size(Vec::new(), 10, 2).unwrap();
+    /// screen.buffer_mut().set_str(0, 0, "ab", &ObjectStyle::new());
+    /// screen.render().unwrap();
+    ///
+    /// // only the changed cell is written on the next render.
+    /// let written = screen.output().len();
+    /// screen.buffer_mut().set_str(1, 0, "c", &ObjectStyle::new());
+    /// screen.render().unwrap();
+    ///
+    /// assert_eq!(&screen.output()[written..], b"\x1B[1;2Hc");
+    /// ```
+    pub fn with_size(output: W, width: u16, height: u16) -> io::Result<Screen<W>> {
+        Ok(Screen {
+            output: AlternateScreen::from(output)?,
+            buffer: Buffer::new(width, height),
+            previous: Buffer::new(width, height),
+            redraw: true,
+        })
+    }
+
+    /// Get the buffer that is rendered on the next `render()`.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Get the buffer that is rendered on the next `render()` to draw into.
+    pub fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.buffer
+    }
+
+    /// Get a reference to the output the screen is rendered to.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Change the size of the screen, for example when the terminal is resized.
+    ///
+    /// The content of the buffer is kept as far as it fits, the whole screen is redrawn on the next `render()`.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.buffer.resize(width, height);
+        self.redraw = true;
+    }
+
+    /// Redraw the whole screen on the next `render()`, for example when something else has written to the terminal.
+    pub fn invalidate(&mut self) {
+        self.redraw = true;
+    }
+
+    /// Write the cells that changed since the previous render to the terminal and flush the output.
+    ///
+    /// The colors are reset afterwards, the cursor is left behind the last written cell.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::style::ObjectStyle;
+    /// use self::crossterm::terminal::screen::Screen;
+    ///
+    /// let mut screen = Screen::with_size(Vec::new(), 4, 1).unwrap();
+    /// screen.buffer_mut().set_str(0, 0, "界a", &ObjectStyle::new());
+    /// screen.render().unwrap();
+    ///
+    /// // the wide character takes two columns, so `a` is written right after it without moving the cursor.
+    /// assert!(screen.output().ends_with("\x1B[1;1H界a".as_bytes()));
+    /// ```
+    pub fn render(&mut self) -> io::Result<()> {
+        if self.redraw {
+            queue!(self.output, ResetColor, Clear(ClearType::All))?;
+            self.previous = Buffer::new(self.buffer.width(), self.buffer.height());
+        }
+
+        // the style and position the terminal is at, `None` when the position is not known.
+        let mut style = ObjectStyle::new();
+        let mut position = None;
+
+        for (x, y, cell) in self.buffer.diff(&self.previous) {
+            // the second cell of an wide character is covered when that character is written.
+            if cell.continuation {
+                continue;
+            }
+
+            if position != Some((x, y)) {
+                queue!(self.output, Goto(x, y))?;
+            }
+
+            if cell.style != style {
+                write_style(&mut self.output, &cell.style)?;
+                style = cell.style.clone();
+            }
+
+            queue!(self.output, Print(cell.ch))?;
+
+            // the terminal may wrap to the next row after the last column, so the position is not known then.
+            let next = x + buffer::char_width(cell.ch);
+            position = if next < self.buffer.width() {
+                Some((next, y))
+            } else {
+                None
+            };
+        }
+
+        if style != ObjectStyle::new() {
+            queue!(self.output, ResetColor)?;
+        }
+
+        self.output.flush()?;
+        self.previous.clone_from(&self.buffer);
+        self.redraw = false;
+        Ok(())
+    }
+}
+
+/// Write the given style, the previous style is reset first so that no colors or attributes are left from it.
+fn write_style<W: Write + TerminalOutput>(output: &mut W, style: &ObjectStyle) -> io::Result<()> {
+    queue!(*output, ResetColor)?;
+
+    if let Some(fg) = style.fg_color {
+        queue!(*output, SetFg(fg))?;
+    }
+    if let Some(bg) = style.bg_color {
+        queue!(*output, SetBg(bg))?;
+    }
+
+    // the colors and attributes are already reset, an reset after the colors would reset them.
+    for attr in style.attrs.iter().filter(|attr| **attr != Attribute::Reset) {
+        queue!(*output, SetAttr(*attr))?;
+    }
+
+    if let Some(underline_style) = style.underline_style {
+        queue!(*output, SetUnderlineStyle(underline_style))?;
+    }
+    if let Some(underline_color) = style.underline_color {
+        queue!(*output, SetUnderlineColor(underline_color))?;
+    }
 
-    command
+    Ok(())
 }
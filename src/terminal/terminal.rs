@@ -3,26 +3,84 @@
 
 use super::*;
 use shared::functions;
-use {Construct, Context};
+use Construct;
 
-use std::ops::Drop;
+use std::io::{self, Stdout, Write};
 
 /// Struct that stores an specific platform implementation for terminal related actions.
-pub struct Terminal {
-    terminal: Option<Box<ITerminal>>,
+///
+/// The terminal actions are written to the output `W`, which is stdout by default.
+pub struct Terminal<W: Write = Stdout> {
+    terminal: Option<Box<dyn ITerminal>>,
+    output: W,
 }
 
-impl Terminal {
+impl Terminal<Stdout> {
     /// Create new terminal instance whereon terminal related actions can be performed.
-    pub fn new() -> Terminal {
+    pub fn new() -> Terminal<Stdout> {
         #[cfg(target_os = "windows")]
         let terminal =
-            functions::get_module::<Box<ITerminal>>(WinApiTerminal::new(), AnsiTerminal::new());
+            functions::get_module::<Box<dyn ITerminal>>(WinApiTerminal::new(), AnsiTerminal::new());
 
         #[cfg(not(target_os = "windows"))]
-        let terminal = Some(AnsiTerminal::new() as Box<ITerminal>);
+        let terminal = Some(AnsiTerminal::new() as Box<dyn ITerminal>);
 
-        Terminal { terminal: terminal }
+        Terminal {
+            terminal,
+            output: io::stdout(),
+        }
+    }
+}
+
+impl<W: Write> Terminal<W> {
+    /// Create new terminal instance that writes the terminal actions to the given output,
+    /// like stderr, an file or an `Vec<u8>`.
+    ///
+    /// The actions are always written as `ANSI escape codes`, because the `WINAPI` can not write to an arbitrary output.
+    /// Note that `terminal_size()` still returns the size of the terminal the process runs in.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    ///
+    /// extern crate crossterm;
+    /// use crossterm::terminal::{ClearType, Terminal};
+    ///
+    /// let mut term = Terminal::from_output(Vec::new());
+    /// term.clear(ClearType::All).unwrap();
+    ///
+    /// assert_eq!(term.output(), b"\x1B[2J");
+    ///
+    /// ```
+    pub fn from_output(output: W) -> Terminal<W> {
+        Terminal {
+            terminal: Some(AnsiTerminal::new() as Box<dyn ITerminal>),
+            output,
+        }
+    }
+
+    /// Create new terminal instance that performs the terminal actions with the given implementation,
+    /// for example the `VirtualTerminal` in tests.
+    pub fn with_implementation(terminal: Box<dyn ITerminal>, output: W) -> Terminal<W> {
+        Terminal {
+            terminal: Some(terminal),
+            output,
+        }
+    }
+
+    /// Get a reference to the output the terminal actions are written to.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Get a mutable reference to the output the terminal actions are written to.
+    pub fn output_mut(&mut self) -> &mut W {
+        &mut self.output
+    }
+
+    /// Consume the terminal and get the output the terminal actions are written to.
+    pub fn into_output(self) -> W {
+        self.output
     }
 
     /// Clear the current cursor by specifying the clear type
@@ -37,20 +95,21 @@ impl Terminal {
     /// let mut term = terminal::terminal();
     ///
     /// // clear all cells in terminal.
-    /// term.clear(terminal::ClearType::All);
+    /// term.clear(terminal::ClearType::All).unwrap();
     /// // clear all cells from the cursor position downwards in terminal.
-    /// term.clear(terminal::ClearType::FromCursorDown);
+    /// term.clear(terminal::ClearType::FromCursorDown).unwrap();
     /// // clear all cells from the cursor position upwards in terminal.
-    /// term.clear(terminal::ClearType::FromCursorUp);
+    /// term.clear(terminal::ClearType::FromCursorUp).unwrap();
     /// // clear current line cells in terminal.
-    /// term.clear(terminal::ClearType::CurrentLine);
+    /// term.clear(terminal::ClearType::CurrentLine).unwrap();
     /// // clear all cells from cursor position until new line in terminal.
-    /// term.clear(terminal::ClearType::UntilNewLine);
+    /// term.clear(terminal::ClearType::UntilNewLine).unwrap();
     ///
     /// ```
-    pub fn clear(&mut self, clear_type: ClearType) {
-        if let Some(ref terminal) = self.terminal {
-            terminal.clear(clear_type);
+    pub fn clear(&mut self, clear_type: ClearType) -> io::Result<()> {
+        match self.terminal {
+            Some(ref terminal) => terminal.clear(&mut self.output, clear_type),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -65,15 +124,17 @@ impl Terminal {
     ///
     /// let mut term = terminal::terminal();
     ///
-    /// let size = term.terminal_size();
-    /// println!("{:?}", size);
+    /// match term.terminal_size() {
+    ///     Ok((width, height)) => println!("The terminal is {} by {} cells", width, height),
+    ///     Err(e) => println!("The terminal size is not available: {}", e),
+    /// }
     ///
     /// ```
-    pub fn terminal_size(&mut self) -> (u16, u16) {
-        if let Some(ref terminal) = self.terminal {
-            return terminal.terminal_size();
+    pub fn terminal_size(&mut self) -> io::Result<(u16, u16)> {
+        match self.terminal {
+            Some(ref terminal) => terminal.terminal_size(),
+            None => Err(functions::not_supported()),
         }
-        (0, 0)
     }
 
     /// Scroll `n` lines up in the current terminal.
@@ -88,12 +149,13 @@ impl Terminal {
     /// let mut term = terminal::terminal();
     ///
     /// // scroll up by 5 lines
-    /// let size = term.scroll_up(5);
+    /// term.scroll_up(5).unwrap();
     ///
     /// ```
-    pub fn scroll_up(&mut self, count: i16) {
-        if let Some(ref terminal) = self.terminal {
-            terminal.scroll_up(count);
+    pub fn scroll_up(&mut self, count: i16) -> io::Result<()> {
+        match self.terminal {
+            Some(ref terminal) => terminal.scroll_up(&mut self.output, count),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -109,12 +171,13 @@ impl Terminal {
     /// let mut term = terminal::terminal();
     ///
     /// // scroll down by 5 lines
-    /// let size = term.scroll_down(5);
+    /// term.scroll_down(5).unwrap();
     ///
     /// ```
-    pub fn scroll_down(&mut self, count: i16) {
-        if let Some(ref terminal) = self.terminal {
-            terminal.scroll_down(count);
+    pub fn scroll_down(&mut self, count: i16) -> io::Result<()> {
+        match self.terminal {
+            Some(ref terminal) => terminal.scroll_down(&mut self.output, count),
+            None => Err(functions::not_supported()),
         }
     }
 
@@ -130,16 +193,23 @@ impl Terminal {
     /// let mut term = terminal::terminal();
     ///
     /// // Set of the size to X: 10 and Y: 10
-    /// let size = term.set_size(10,10);
+    /// term.set_size(10,10).unwrap();
     ///
     /// ```
-    pub fn set_size(&mut self, width: i16, height: i16) {
-        if let Some(ref terminal) = self.terminal {
-            terminal.set_size(width, height);
+    pub fn set_size(&mut self, width: i16, height: i16) -> io::Result<()> {
+        match self.terminal {
+            Some(ref terminal) => terminal.set_size(&mut self.output, width, height),
+            None => Err(functions::not_supported()),
         }
     }
 }
 
+impl Default for Terminal<Stdout> {
+    fn default() -> Terminal<Stdout> {
+        Terminal::new()
+    }
+}
+
 /// Get an Terminal implementation whereon terminal related actions can be performed.
 ///
 /// Check `/examples/terminal` in the libary for more spesific examples.
@@ -154,7 +224,7 @@ impl Terminal {
 /// let mut term = terminal::terminal();
 ///
 /// // scroll down by 5 lines
-/// let size = term.scroll_down(5);
+/// term.scroll_down(5).unwrap();
 ///
 /// ```
 ///
@@ -0,0 +1,583 @@
+//! This module contains the `VirtualTerminal`, an in-memory terminal that can be used instead of an real terminal in tests.
+//!
+//! The `VirtualTerminal` implements `ITerminalCursor`, `ITerminal` and `ITerminalColor`,
+//! instead of writing escape codes it records the cursor position, the cell contents with their style, the saved cursor position
+//! and whether the alternate screen is active into an grid of cells.
+//! It also implements `Write`, the escape sequences written to it are interpreted with the `vt` parser
+//! and the text is placed at the cursor position with the current colors and attributes.
+//! So it can also be used as output of the `queue!` macro, the `Screen` or any other code that writes escape sequences.
+//!
+//! Nothing is read from or written to the real terminal, so it can also be used when the process runs without terminal.
+//!
+//! # Example
+//!
+//! ```rust
+//! extern crate crossterm;
+//!
+//! use self::crossterm::style::Color;
+//! use self::crossterm::terminal::virtual_terminal::VirtualTerminal;
+//!
+//! let terminal = VirtualTerminal::new(20, 5);
+//!
+//! let mut cursor = terminal.cursor();
+//! cursor.goto(3, 1).unwrap().print("Hello").unwrap();
+//!
+//! let mut color = terminal.color();
+//! color.set_fg(Color::Red).unwrap();
+//! cursor.move_down(1).unwrap().print("!").unwrap();
+//!
+//! assert_eq!(terminal.line(1), "   Hello");
+//! assert_eq!(terminal.cell(8, 2).unwrap().style.fg_color, Some(Color::Red));
+//! assert_eq!(cursor.pos().unwrap(), (9, 2));
+//! ```
+
+use super::buffer::{self, Buffer, Cell};
+use super::{ClearType, ITerminal, Terminal};
+use cursor::{ITerminalCursor, TerminalCursor};
+use style::{AnsiColor, Color, ColorType, ITerminalColor, ObjectStyle, StylePolicy, TerminalColor};
+use vt::{Action, Graphic, Parser};
+use {Error, ErrorKind, TerminalOutput};
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// An in-memory terminal, the clones of an `VirtualTerminal` share the same state.
+///
+/// #Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate crossterm;
+///
+/// use self::crossterm::cursor::Goto;
+/// use self::crossterm::style::{Color, SetBg};
+/// use self::crossterm::terminal::virtual_terminal::VirtualTerminal;
+/// use self::crossterm::Print;
+///
+/// fn main() {
+///     let terminal = VirtualTerminal::new(10, 3);
+///
+///     // the escape sequences written to the terminal are interpreted.
+///     let mut output = terminal.clone();
+///     execute!(output, Goto(2, 2), SetBg(Color::Blue), Print("ok")).unwrap();
+///
+///     assert_eq!(terminal.line(2), "  ok");
+///     assert_eq!(terminal.cell(3, 2).unwrap().style.bg_color, Some(Color::Blue));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct VirtualTerminal {
+    state: Rc<RefCell<State>>,
+}
+
+/// The state of the virtual terminal.
+struct State {
+    /// The screen that is shown, the alternate screen when it is active.
+    screen: Buffer,
+    /// The main screen and its cursor position while the alternate screen is active.
+    main_screen: Option<(Buffer, (u16, u16))>,
+    cursor: (u16, u16),
+    saved_position: Option<(u16, u16)>,
+    cursor_visible: bool,
+    /// The style new text is written with.
+    style: ObjectStyle,
+    /// Whether the cursor is at the last column and the next character is written on the next row.
+    wrap_pending: bool,
+    /// The parser of the bytes that are written to the terminal.
+    parser: Parser,
+}
+
+impl VirtualTerminal {
+    /// Create an virtual terminal with the given number of columns and rows, filled with empty cells.
+    pub fn new(width: u16, height: u16) -> VirtualTerminal {
+        VirtualTerminal {
+            state: Rc::new(RefCell::new(State {
+                screen: Buffer::new(width, height),
+                main_screen: None,
+                cursor: (0, 0),
+                saved_position: None,
+                cursor_visible: true,
+                style: ObjectStyle::new(),
+                wrap_pending: false,
+                parser: Parser::new(),
+            })),
+        }
+    }
+
+    /// Get an `TerminalCursor` that performs its actions on this virtual terminal and prints to it.
+    pub fn cursor(&self) -> TerminalCursor<VirtualTerminal> {
+        TerminalCursor::with_implementation(Box::new(self.clone()), self.clone())
+    }
+
+    /// Get an `Terminal` that performs its actions on this virtual terminal.
+    pub fn terminal(&self) -> Terminal<VirtualTerminal> {
+        Terminal::with_implementation(Box::new(self.clone()), self.clone())
+    }
+
+    /// Get an `TerminalColor` that sets the colors of this virtual terminal.
+    ///
+    /// The colors are always set, the virtual terminal is no real output the style policy could be detected for.
+    pub fn color(&self) -> TerminalColor<VirtualTerminal> {
+        let mut color = TerminalColor::with_implementation(Box::new(self.clone()), self.clone());
+        color.set_style_policy(StylePolicy::Always);
+        color
+    }
+
+    /// Get the number of columns of the virtual terminal.
+    pub fn width(&self) -> u16 {
+        self.state.borrow().screen.width()
+    }
+
+    /// Get the number of rows of the virtual terminal.
+    pub fn height(&self) -> u16 {
+        self.state.borrow().screen.height()
+    }
+
+    /// Get the 0-based cursor position (x,y).
+    pub fn cursor_position(&self) -> (u16, u16) {
+        self.state.borrow().cursor
+    }
+
+    /// Get the cursor position that is saved with `save_position()`, `None` when no position is saved.
+    pub fn saved_position(&self) -> Option<(u16, u16)> {
+        self.state.borrow().saved_position
+    }
+
+    /// Check whether the cursor is shown.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::terminal::virtual_terminal::VirtualTerminal;
+    ///
+    /// let terminal = VirtualTerminal::new(20, 5);
+    ///
+    /// terminal.cursor().hide().unwrap();
+    /// assert!(!terminal.is_cursor_visible());
+    /// ```
+    pub fn is_cursor_visible(&self) -> bool {
+        self.state.borrow().cursor_visible
+    }
+
+    /// Get the style new text is written with.
+    pub fn style(&self) -> ObjectStyle {
+        self.state.borrow().style.clone()
+    }
+
+    /// Check whether the alternate screen is active.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::terminal::screen::AlternateScreen;
+    /// use self::crossterm::terminal::virtual_terminal::VirtualTerminal;
+    ///
+    /// let terminal = VirtualTerminal::new(20, 5);
+    ///
+    /// {
+    ///     let _screen = AlternateScreen::from(terminal.clone()).unwrap();
+    ///     assert!(terminal.is_alternate_screen());
+    /// }
+    ///
+    /// assert!(!terminal.is_alternate_screen());
+    /// ```
+    pub fn is_alternate_screen(&self) -> bool {
+        self.state.borrow().main_screen.is_some()
+    }
+
+    /// Get the cell at the given position of the screen that is shown, `None` when the position is outside of the terminal.
+    pub fn cell(&self, x: u16, y: u16) -> Option<Cell> {
+        self.state.borrow().screen.get(x, y).cloned()
+    }
+
+    /// Get an copy of the cells of the screen that is shown.
+    pub fn buffer(&self) -> Buffer {
+        self.state.borrow().screen.clone()
+    }
+
+    /// Get the text of the given row without the spaces at the end, an empty string when the row is outside of the terminal.
+    pub fn line(&self, y: u16) -> String {
+        let state = self.state.borrow();
+        let line: String = (0..state.screen.width())
+            .filter_map(|x| state.screen.get(x, y))
+            .filter(|cell| !cell.continuation)
+            .map(|cell| cell.ch)
+            .collect();
+
+        line.trim_end().to_string()
+    }
+
+    /// Get the text of all rows separated by new lines, without the spaces at the end of the rows.
+    pub fn contents(&self) -> String {
+        let lines: Vec<String> = (0..self.height()).map(|y| self.line(y)).collect();
+        lines.join("\n")
+    }
+}
+
+impl State {
+    fn goto(&mut self, x: u16, y: u16) {
+        // like an real terminal the cursor stops at the edges of the screen.
+        self.cursor = (
+            x.min(self.screen.width().saturating_sub(1)),
+            y.min(self.screen.height().saturating_sub(1)),
+        );
+        self.wrap_pending = false;
+    }
+
+    /// Clear the cells from the start position up to and including the end position, row by row.
+    fn clear_range(&mut self, start: (u16, u16), end: (u16, u16)) {
+        let width = self.screen.width();
+
+        for y in start.1..=end.1 {
+            let from = if y == start.1 { start.0 } else { 0 };
+            let to = if y == end.1 {
+                end.0
+            } else {
+                width.saturating_sub(1)
+            };
+
+            for x in from..=to {
+                self.screen.set(x, y, Cell::default());
+            }
+        }
+    }
+
+    fn clear(&mut self, clear_type: ClearType) {
+        let (x, y) = self.cursor;
+        let last_column = self.screen.width().saturating_sub(1);
+        let last_row = self.screen.height().saturating_sub(1);
+
+        match clear_type {
+            ClearType::All => self.screen.clear(),
+            ClearType::FromCursorDown => self.clear_range((x, y), (last_column, last_row)),
+            ClearType::FromCursorUp => self.clear_range((0, 0), (x, y)),
+            ClearType::CurrentLine => self.clear_range((0, y), (last_column, y)),
+            ClearType::UntilNewLine => self.clear_range((x, y), (last_column, y)),
+        }
+    }
+
+    /// Move the content of the screen up, the rows at the bottom become empty.
+    fn scroll_up(&mut self, count: u16) {
+        let (width, height) = (self.screen.width(), self.screen.height());
+
+        for y in 0..height {
+            for x in 0..width {
+                let cell = match y.checked_add(count) {
+                    Some(source) if source < height => self.screen.get(x, source).cloned(),
+                    _ => None,
+                };
+                self.screen.set(x, y, cell.unwrap_or_default());
+            }
+        }
+    }
+
+    /// Move the content of the screen down, the rows at the top become empty.
+    fn scroll_down(&mut self, count: u16) {
+        let (width, height) = (self.screen.width(), self.screen.height());
+
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let cell = match y.checked_sub(count) {
+                    Some(source) => self.screen.get(x, source).cloned(),
+                    None => None,
+                };
+                self.screen.set(x, y, cell.unwrap_or_default());
+            }
+        }
+    }
+
+    /// Move the cursor to the start of the next row, the screen scrolls when the cursor is at the last row.
+    fn new_line(&mut self) {
+        if self.cursor.1 + 1 >= self.screen.height() {
+            self.scroll_up(1);
+        } else {
+            self.cursor.1 += 1;
+        }
+        self.cursor.0 = 0;
+        self.wrap_pending = false;
+    }
+
+    fn print(&mut self, ch: char) {
+        let width = buffer::char_width(ch);
+
+        // an combining character is left out like in the `Buffer`, and an character wider than the screen never fits.
+        if width == 0 || width > self.screen.width() {
+            return;
+        }
+
+        // an wide character that does not fit at the end of the row is written at the start of the next row.
+        if self.wrap_pending || self.cursor.0 + width > self.screen.width() {
+            self.new_line();
+        }
+
+        let (x, y) = self.cursor;
+        self.screen.set_char(x, y, ch, &self.style);
+
+        if x + width < self.screen.width() {
+            self.cursor.0 += width;
+        } else {
+            self.wrap_pending = true;
+        }
+    }
+
+    fn switch_to_alternate_screen(&mut self) {
+        if self.main_screen.is_none() {
+            let screen = Buffer::new(self.screen.width(), self.screen.height());
+            let main_screen = ::std::mem::replace(&mut self.screen, screen);
+            self.main_screen = Some((main_screen, self.cursor));
+        }
+    }
+
+    fn switch_to_main_screen(&mut self) {
+        if let Some((main_screen, cursor)) = self.main_screen.take() {
+            self.screen = main_screen;
+            self.goto(cursor.0, cursor.1);
+        }
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.screen.resize(width, height);
+        if let Some((ref mut main_screen, _)) = self.main_screen {
+            main_screen.resize(width, height);
+        }
+
+        let (x, y) = self.cursor;
+        self.goto(x, y);
+    }
+
+    fn set_graphics(&mut self, graphics: Vec<Graphic>) {
+        for graphic in graphics {
+            match graphic {
+                Graphic::Reset => self.style = ObjectStyle::new(),
+                Graphic::Attribute(attr) => self.style.apply_attr(attr),
+                Graphic::Foreground(color) => self.style.fg_color = Some(color),
+                Graphic::Background(color) => self.style.bg_color = Some(color),
+                Graphic::DefaultForeground => self.style.fg_color = None,
+                Graphic::DefaultBackground => self.style.bg_color = None,
+                Graphic::UnderlineStyle(style) => self.style.underline_style = Some(style),
+                Graphic::UnderlineColor(color) => self.style.underline_color = Some(color),
+                Graphic::DefaultUnderlineColor => self.style.underline_color = None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Perform an action that is parsed from the bytes written to the terminal.
+    fn perform(&mut self, action: Action) {
+        let (x, y) = self.cursor;
+
+        match action {
+            Action::Print(text) => {
+                for ch in text.chars() {
+                    self.print(ch);
+                }
+            }
+            Action::LineFeed => self.new_line(),
+            Action::CarriageReturn => self.goto(0, y),
+            Action::Backspace => self.goto(x.saturating_sub(1), y),
+            Action::Tab => self.goto((x / 8 + 1) * 8, y),
+            Action::CursorGoto(x, y) => self.goto(x, y),
+            Action::CursorUp(count) => self.goto(x, y.saturating_sub(count)),
+            Action::CursorDown(count) => self.goto(x, y.saturating_add(count)),
+            Action::CursorForward(count) => self.goto(x.saturating_add(count), y),
+            Action::CursorBack(count) => self.goto(x.saturating_sub(count), y),
+            Action::CursorNextLine(count) => self.goto(0, y.saturating_add(count)),
+            Action::CursorPreviousLine(count) => self.goto(0, y.saturating_sub(count)),
+            Action::CursorColumn(column) => self.goto(column, y),
+            Action::CursorRow(row) => self.goto(x, row),
+            Action::SaveCursor => self.saved_position = Some(self.cursor),
+            Action::RestoreCursor => {
+                let (x, y) = self.saved_position.unwrap_or((0, 0));
+                self.goto(x, y);
+            }
+            Action::Index => {
+                if y + 1 >= self.screen.height() {
+                    self.scroll_up(1);
+                } else {
+                    self.goto(x, y + 1);
+                }
+            }
+            Action::ReverseIndex => {
+                if y == 0 {
+                    self.scroll_down(1);
+                } else {
+                    self.goto(x, y - 1);
+                }
+            }
+            Action::EraseDisplay(clear_type) => self.clear(clear_type),
+            Action::EraseLine(ClearType::FromCursorUp) => self.clear_range((0, y), (x, y)),
+            Action::EraseLine(ClearType::UntilNewLine) => self.clear(ClearType::UntilNewLine),
+            Action::EraseLine(_) => self.clear(ClearType::CurrentLine),
+            Action::ScrollUp(count) => self.scroll_up(count),
+            Action::ScrollDown(count) => self.scroll_down(count),
+            Action::SetSize(width, height) if width > 0 && height > 0 => self.resize(width, height),
+            Action::AlternateScreen(true) => self.switch_to_alternate_screen(),
+            Action::AlternateScreen(false) => self.switch_to_main_screen(),
+            Action::SetGraphics(graphics) => self.set_graphics(graphics),
+            Action::ShowCursor(visible) => self.cursor_visible = visible,
+            Action::Reset => {
+                self.switch_to_main_screen();
+                self.screen.clear();
+                self.style = ObjectStyle::new();
+                self.saved_position = None;
+                self.cursor_visible = true;
+                self.goto(0, 0);
+            }
+            _ => {}
+        }
+    }
+
+    fn write(&mut self, buf: &[u8]) {
+        for action in self.parser.advance(buf) {
+            self.perform(action);
+        }
+    }
+}
+
+impl Write for VirtualTerminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.state.borrow_mut().write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl TerminalOutput for VirtualTerminal {
+    fn is_tty(&self) -> bool {
+        false
+    }
+}
+
+impl ITerminalCursor for VirtualTerminal {
+    fn goto(&self, _output: &mut dyn Write, x: u16, y: u16) -> io::Result<()> {
+        self.state.borrow_mut().goto(x, y);
+        Ok(())
+    }
+
+    fn pos(&self) -> io::Result<(u16, u16)> {
+        Ok(self.cursor_position())
+    }
+
+    fn move_up(&self, _output: &mut dyn Write, count: u16) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let (x, y) = state.cursor;
+        state.goto(x, y.saturating_sub(count));
+        Ok(())
+    }
+
+    fn move_right(&self, _output: &mut dyn Write, count: u16) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let (x, y) = state.cursor;
+        state.goto(x.saturating_add(count), y);
+        Ok(())
+    }
+
+    fn move_down(&self, _output: &mut dyn Write, count: u16) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let (x, y) = state.cursor;
+        state.goto(x, y.saturating_add(count));
+        Ok(())
+    }
+
+    fn move_left(&self, _output: &mut dyn Write, count: u16) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let (x, y) = state.cursor;
+        state.goto(x.saturating_sub(count), y);
+        Ok(())
+    }
+
+    fn save_position(&mut self, _output: &mut dyn Write) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        state.saved_position = Some(state.cursor);
+        Ok(())
+    }
+
+    fn reset_position(&self, _output: &mut dyn Write) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let (x, y) = state.saved_position.unwrap_or((0, 0));
+        state.goto(x, y);
+        Ok(())
+    }
+
+    fn hide(&self, _output: &mut dyn Write) -> io::Result<()> {
+        self.state.borrow_mut().cursor_visible = false;
+        Ok(())
+    }
+
+    fn show(&self, _output: &mut dyn Write) -> io::Result<()> {
+        self.state.borrow_mut().cursor_visible = true;
+        Ok(())
+    }
+}
+
+impl ITerminal for VirtualTerminal {
+    fn clear(&self, _output: &mut dyn Write, clear_type: ClearType) -> io::Result<()> {
+        self.state.borrow_mut().clear(clear_type);
+        Ok(())
+    }
+
+    fn terminal_size(&self) -> io::Result<(u16, u16)> {
+        // like the real terminals the size is the position of the last column and row.
+        let state = self.state.borrow();
+        Ok((
+            state.screen.width().saturating_sub(1),
+            state.screen.height().saturating_sub(1),
+        ))
+    }
+
+    fn scroll_up(&self, _output: &mut dyn Write, count: i16) -> io::Result<()> {
+        if count > 0 {
+            self.state.borrow_mut().scroll_up(count as u16);
+        }
+        Ok(())
+    }
+
+    fn scroll_down(&self, _output: &mut dyn Write, count: i16) -> io::Result<()> {
+        if count > 0 {
+            self.state.borrow_mut().scroll_down(count as u16);
+        }
+        Ok(())
+    }
+
+    fn set_size(&self, _output: &mut dyn Write, width: i16, height: i16) -> io::Result<()> {
+        if width <= 0 || height <= 0 {
+            return Err(Error::new(
+                ErrorKind::OutOfRange,
+                "Cannot set the terminal size lower than 1",
+            )
+            .into());
+        }
+
+        self.state.borrow_mut().resize(width as u16, height as u16);
+        Ok(())
+    }
+}
+
+impl ITerminalColor for VirtualTerminal {
+    fn set_fg(&self, _output: &mut dyn Write, fg_color: Color) -> io::Result<()> {
+        self.state.borrow_mut().style.fg_color = Some(fg_color);
+        Ok(())
+    }
+
+    fn set_bg(&self, _output: &mut dyn Write, bg_color: Color) -> io::Result<()> {
+        self.state.borrow_mut().style.bg_color = Some(bg_color);
+        Ok(())
+    }
+
+    fn reset(&self, _output: &mut dyn Write) -> io::Result<()> {
+        self.state.borrow_mut().style = ObjectStyle::new();
+        Ok(())
+    }
+
+    fn color_value(&self, color: Color, color_type: ColorType) -> String {
+        AnsiColor.color_value(color, color_type)
+    }
+}
@@ -4,8 +4,11 @@
 use super::{ClearType, ITerminal};
 use cursor::cursor;
 use kernel::windows_kernel::{kernel, terminal};
+use shared::functions;
 use winapi::um::wincon::{CONSOLE_SCREEN_BUFFER_INFO, COORD, SMALL_RECT};
-use Construct;
+use {Construct, Error, ErrorKind};
+
+use std::io::{self, Write};
 
 /// This struct is an windows implementation for terminal related actions.
 pub struct WinApiTerminal;
@@ -17,9 +20,11 @@ impl Construct for WinApiTerminal {
 }
 
 impl ITerminal for WinApiTerminal {
-    fn clear(&self, clear_type: ClearType) {
-        let csbi = kernel::get_console_screen_buffer_info();
-        let pos = cursor().pos();
+    fn clear(&self, output: &mut dyn Write, clear_type: ClearType) -> io::Result<()> {
+        output.flush()?;
+
+        let csbi = kernel::get_console_screen_buffer_info()?;
+        let pos = cursor().pos()?;
 
         match clear_type {
             ClearType::All => clear_entire_screen(csbi),
@@ -27,49 +32,51 @@ impl ITerminal for WinApiTerminal {
             ClearType::FromCursorUp => clear_before_cursor(pos, csbi),
             ClearType::CurrentLine => clear_current_line(pos, csbi),
             ClearType::UntilNewLine => clear_until_line(pos, csbi),
-        };
+        }
     }
 
-    fn terminal_size(&self) -> (u16, u16) {
+    fn terminal_size(&self) -> io::Result<(u16, u16)> {
         terminal::terminal_size()
     }
 
-    fn scroll_up(&self, count: i16) {
+    fn scroll_up(&self, _output: &mut dyn Write, _count: i16) -> io::Result<()> {
         // yet to be inplemented
+        Err(functions::not_supported())
     }
 
-    fn scroll_down(&self, count: i16) {
-        let csbi = kernel::get_console_screen_buffer_info();
-        let mut srct_window;
+    fn scroll_down(&self, output: &mut dyn Write, count: i16) -> io::Result<()> {
+        output.flush()?;
+
+        let csbi = kernel::get_console_screen_buffer_info()?;
 
         // Set srctWindow to the current window size and location.
-        srct_window = csbi.srWindow;
+        let mut srct_window = csbi.srWindow;
 
         // Check whether the window is too close to the screen buffer top
         if srct_window.Bottom < csbi.dwSize.Y - count {
             srct_window.Top += count; // move top down
             srct_window.Bottom += count; // move bottom down
 
-            let success = kernel::set_console_info(true, &mut srct_window);
-            if success {
-                panic!("Something went wrong when scrolling down");
-            }
+            kernel::set_console_info(true, &srct_window)?;
         }
+
+        Ok(())
     }
 
     /// Set the current terminal size
-    fn set_size(&self, width: i16, height: i16) {
+    fn set_size(&self, output: &mut dyn Write, width: i16, height: i16) -> io::Result<()> {
         if width <= 0 {
-            panic!("Cannot set the terminal width lower than 1");
+            return Err(out_of_range("Cannot set the terminal width lower than 1"));
         }
 
         if height <= 0 {
-            panic!("Cannot set the terminal height lower then 1")
+            return Err(out_of_range("Cannot set the terminal height lower then 1"));
         }
 
+        output.flush()?;
+
         // Get the position of the current console window
-        let csbi = kernel::get_console_screen_buffer_info();
-        let mut success = false;
+        let csbi = kernel::get_console_screen_buffer_info()?;
 
         // If the buffer is smaller than this new window size, resize the
         // buffer to be large enough.  Include window position.
@@ -80,16 +87,16 @@ impl ITerminal for WinApiTerminal {
         };
 
         if csbi.dwSize.X < csbi.srWindow.Left + width {
-            if csbi.srWindow.Left >= i16::max_value() - width {
-                panic!("Argument out of range when setting terminal width.");
+            if csbi.srWindow.Left >= i16::MAX - width {
+                return Err(out_of_range("Argument out of range when setting terminal width."));
             }
 
             size.X = csbi.srWindow.Left + width;
             resize_buffer = true;
         }
         if csbi.dwSize.Y < csbi.srWindow.Top + height {
-            if csbi.srWindow.Top >= i16::max_value() - height {
-                panic!("Argument out of range when setting terminal height");
+            if csbi.srWindow.Top >= i16::MAX - height {
+                return Err(out_of_range("Argument out of range when setting terminal height"));
             }
 
             size.Y = csbi.srWindow.Top + height;
@@ -97,11 +104,7 @@ impl ITerminal for WinApiTerminal {
         }
 
         if resize_buffer {
-            success = kernel::set_console_screen_buffer_size(size);
-
-            if !success {
-                panic!("Something went wrong when setting screen buffer size.");
-            }
+            kernel::set_console_screen_buffer_size(size)?;
         }
 
         let mut fsr_window: SMALL_RECT = csbi.srWindow;
@@ -109,33 +112,38 @@ impl ITerminal for WinApiTerminal {
         fsr_window.Bottom = fsr_window.Top + height;
         fsr_window.Right = fsr_window.Left + width;
 
-        let success = kernel::set_console_info(true, &fsr_window);
+        kernel::set_console_info(true, &fsr_window)?;
 
-        if success {
-            // If we resized the buffer, un-resize it.
-            if resize_buffer {
-                kernel::set_console_screen_buffer_size(csbi.dwSize);
-            }
+        // If we resized the buffer, un-resize it.
+        if resize_buffer {
+            kernel::set_console_screen_buffer_size(csbi.dwSize)?;
+        }
 
-            let bounds = kernel::get_largest_console_window_size();
+        let bounds = kernel::get_largest_console_window_size()?;
 
-            if width > bounds.X {
-                panic!(
-                    "Argument width: {} out of range when setting terminal width.",
-                    width
-                );
-            }
-            if height > bounds.Y {
-                panic!(
-                    "Argument height: {} out of range when setting terminal height",
-                    height
-                );
-            }
+        if width > bounds.X {
+            return Err(out_of_range(format!(
+                "Argument width: {} out of range when setting terminal width.",
+                width
+            )));
+        }
+        if height > bounds.Y {
+            return Err(out_of_range(format!(
+                "Argument height: {} out of range when setting terminal height",
+                height
+            )));
         }
+
+        Ok(())
     }
 }
 
-pub fn clear_after_cursor(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
+/// The error that is returned when the terminal can not be set to the requested size.
+fn out_of_range<M: Into<String>>(message: M) -> io::Error {
+    Error::new(ErrorKind::OutOfRange, message).into()
+}
+
+pub fn clear_after_cursor(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) -> io::Result<()> {
     let (mut x, mut y) = pos;
 
     // if cursor position is at the outer right position
@@ -152,10 +160,10 @@ pub fn clear_after_cursor(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
     // get sum cells before cursor
     let cells_to_write = csbi.dwSize.X as u32 * csbi.dwSize.Y as u32;
 
-    clear(start_location, cells_to_write);
+    clear(start_location, cells_to_write)
 }
 
-pub fn clear_before_cursor(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
+pub fn clear_before_cursor(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) -> io::Result<()> {
     let (xpos, ypos) = pos;
 
     // one cell after cursor position
@@ -164,38 +172,33 @@ pub fn clear_before_cursor(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
     let y = 0;
 
     // location where to start clearing
-    let start_location = COORD {
-        X: x as i16,
-        Y: y as i16,
-    };
+    let start_location = COORD { X: x, Y: y };
     // get sum cells before cursor
     let cells_to_write = (csbi.dwSize.X as u32 * ypos as u32) + (xpos as u32 + 1);
 
-    clear(start_location, cells_to_write);
+    clear(start_location, cells_to_write)
 }
 
-pub fn clear_entire_screen(csbi: CONSOLE_SCREEN_BUFFER_INFO) {
+pub fn clear_entire_screen(csbi: CONSOLE_SCREEN_BUFFER_INFO) -> io::Result<()> {
     // position x at start
     let x = 0;
     // position y at start
     let y = 0;
 
     // location where to start clearing
-    let start_location = COORD {
-        X: x as i16,
-        Y: y as i16,
-    };
+    let start_location = COORD { X: x, Y: y };
     // get sum cells before cursor
 
     let cells_to_write = csbi.dwSize.X as u32 * csbi.dwSize.Y as u32;
 
-    clear(start_location, cells_to_write);
+    clear(start_location, cells_to_write)?;
 
     // put the cursor back at (0, 0)
-    cursor().goto(0, 0);
+    cursor().goto(0, 0)?;
+    Ok(())
 }
 
-pub fn clear_current_line(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
+pub fn clear_current_line(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) -> io::Result<()> {
     // position x at start
     let x = 0;
     // position y at start
@@ -203,20 +206,21 @@ pub fn clear_current_line(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
 
     // location where to start clearing
     let start_location = COORD {
-        X: x as i16,
+        X: x,
         Y: y as i16,
     };
     // get sum cells before cursor
 
     let cells_to_write = csbi.dwSize.X as u32;
 
-    clear(start_location, cells_to_write);
+    clear(start_location, cells_to_write)?;
 
     // put the cursor back at 1 cell on current row
-    cursor().goto(0, y);
+    cursor().goto(0, y)?;
+    Ok(())
 }
 
-pub fn clear_until_line(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
+pub fn clear_until_line(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) -> io::Result<()> {
     let (x, y) = pos;
 
     // location where to start clearing
@@ -227,29 +231,15 @@ pub fn clear_until_line(pos: (u16, u16), csbi: CONSOLE_SCREEN_BUFFER_INFO) {
     // get sum cells before cursor
     let cells_to_write = (csbi.dwSize.X - x as i16) as u32;
 
-    clear(start_location, cells_to_write);
+    clear(start_location, cells_to_write)?;
 
     // put the cursor back at original cursor position
-    cursor().goto(x, y);
+    cursor().goto(x, y)?;
+    Ok(())
 }
 
-fn clear(start_loaction: COORD, cells_to_write: u32) {
-    let mut cells_written = 0;
-    let mut success = false;
-
-    success =
-        kernel::fill_console_output_character(&mut cells_written, start_loaction, cells_to_write);
-
-    if !success {
-        panic!("Could not clear screen after cursor");
-    }
-
-    cells_written = 0;
-
-    success =
-        kernel::fill_console_output_attribute(&mut cells_written, start_loaction, cells_to_write);
-
-    if !success {
-        panic!("Couldnot reset attributes after cursor");
-    }
+fn clear(start_loaction: COORD, cells_to_write: u32) -> io::Result<()> {
+    kernel::fill_console_output_character(start_loaction, cells_to_write)?;
+    kernel::fill_console_output_attribute(start_loaction, cells_to_write)?;
+    Ok(())
 }
@@ -0,0 +1,166 @@
+//! This module contains an parser for the escape sequences terminals understand (ECMA-48).
+//!
+//! The parser turns an stream of bytes into `Action`s, like `Action::CursorGoto` and `Action::SetGraphics`.
+//! The actions use the `ClearType`, `Color` and `Attribute` types of this crate, so the output of this crate
+//! can be parsed back into the values it was written with.
+//! This can be used to check what an program has written or to post-process logs of colored output.
+//!
+//! # Example
+//!
+//! ```rust
+//! extern crate crossterm;
+//!
+//! use self::crossterm::style::{Attribute, Color};
+//! use self::crossterm::terminal::ClearType;
+//! use self::crossterm::vt::{self, Action, Graphic};
+//!
+//! let actions = vt::parse(b"\x1B[2J\x1B[3;5H\x1B[1;38;5;9mHello\x1B[0m");
+//!
+//! assert_eq!(
+//!     actions,
+//!     vec![
+//!         Action::EraseDisplay(ClearType::All),
+//!         Action::CursorGoto(4, 2),
+//!         Action::SetGraphics(vec![
+//!             Graphic::Attribute(Attribute::Bold),
+//!             Graphic::Foreground(Color::Red),
+//!         ]),
+//!         Action::Print("Hello".to_string()),
+//!         Action::SetGraphics(vec![Graphic::Reset]),
+//!     ]
+//! );
+//! ```
+
+mod parser;
+
+pub use self::parser::Parser;
+
+use style::{Attribute, Color, UnderlineStyle};
+use terminal::ClearType;
+
+/// An action the terminal should perform, parsed from an escape sequence, control character or text.
+///
+/// The positions are 0-based like the cursor positions of this crate, the counts are at least 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Text that should be printed at the cursor position.
+    Print(String),
+    /// Line feed (`\n`).
+    LineFeed,
+    /// Carriage return (`\r`).
+    CarriageReturn,
+    /// Backspace (`\x08`).
+    Backspace,
+    /// Horizontal tab (`\t`).
+    Tab,
+    /// Bell (`\x07`).
+    Bell,
+    /// Any other control character.
+    Control(u8),
+
+    /// Move the cursor to the given position (x,y).
+    CursorGoto(u16, u16),
+    /// Move the cursor the given number of rows up.
+    CursorUp(u16),
+    /// Move the cursor the given number of rows down.
+    CursorDown(u16),
+    /// Move the cursor the given number of columns to the right.
+    CursorForward(u16),
+    /// Move the cursor the given number of columns to the left.
+    CursorBack(u16),
+    /// Move the cursor to the start of the row the given number of rows down.
+    CursorNextLine(u16),
+    /// Move the cursor to the start of the row the given number of rows up.
+    CursorPreviousLine(u16),
+    /// Move the cursor to the given column of the current row.
+    CursorColumn(u16),
+    /// Move the cursor to the given row in the current column.
+    CursorRow(u16),
+    /// Save the cursor position.
+    SaveCursor,
+    /// Move the cursor to the saved position.
+    RestoreCursor,
+    /// Move the cursor one row down, the screen scrolls up at the last row.
+    Index,
+    /// Move the cursor one row up, the screen scrolls down at the first row.
+    ReverseIndex,
+    /// Show or hide the cursor.
+    ShowCursor(bool),
+    /// Ask the terminal for the cursor position.
+    RequestCursorPosition,
+
+    /// Clear (a part of) the screen.
+    EraseDisplay(ClearType),
+    /// Clear (a part of) the row of the cursor, `ClearType::FromCursorUp` clears from the start of the row up to the cursor.
+    EraseLine(ClearType),
+    /// Scroll the content of the screen the given number of rows up.
+    ScrollUp(u16),
+    /// Scroll the content of the screen the given number of rows down.
+    ScrollDown(u16),
+    /// Resize the terminal to the given width and height, in the order `Terminal::set_size()` writes them.
+    SetSize(u16, u16),
+    /// Switch to the alternate screen (`true`) or back to the main screen (`false`).
+    AlternateScreen(bool),
+    /// Set (`true`) or reset (`false`) an terminal mode that has no action of its own.
+    SetMode {
+        private: bool,
+        mode: u16,
+        enabled: bool,
+    },
+    /// Set the title of the terminal window.
+    SetTitle(String),
+    /// Reset the terminal to its initial state.
+    Reset,
+
+    /// Change the colors and attributes of the text that is printed after it.
+    SetGraphics(Vec<Graphic>),
+
+    /// An control sequence that has no action of its own.
+    Csi {
+        private: Option<u8>,
+        params: Vec<u16>,
+        intermediates: Vec<u8>,
+        action: char,
+    },
+    /// An escape sequence that has no action of its own.
+    Escape {
+        intermediates: Vec<u8>,
+        action: char,
+    },
+    /// An operating system command that has no action of its own, without the `ESC ]` and string terminator.
+    OperatingSystemCommand(Vec<u8>),
+    /// An device control string, without the `ESC P` and string terminator.
+    DeviceControl(Vec<u8>),
+}
+
+/// One change of an `Action::SetGraphics`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Graphic {
+    /// Reset all colors and attributes.
+    Reset,
+    /// Apply the attribute.
+    Attribute(Attribute),
+    /// Set the foreground color.
+    Foreground(Color),
+    /// Set the background color.
+    Background(Color),
+    /// Reset the foreground color to the default of the terminal.
+    DefaultForeground,
+    /// Reset the background color to the default of the terminal.
+    DefaultBackground,
+    /// Underline the text with the given style (`4:n`).
+    UnderlineStyle(UnderlineStyle),
+    /// Set the underline color.
+    UnderlineColor(Color),
+    /// Reset the underline color to the default of the terminal.
+    DefaultUnderlineColor,
+    /// An parameter this crate has no type for.
+    Unknown(u16),
+}
+
+/// Parse an complete stream of bytes into actions.
+///
+/// An escape sequence or character that is cut off at the end is ignored, use an `Parser` to parse an stream in parts.
+pub fn parse(bytes: &[u8]) -> Vec<Action> {
+    Parser::new().advance(bytes)
+}
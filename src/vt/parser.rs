@@ -0,0 +1,718 @@
+//! This module contains the state machine that parses the escape sequences, it follows the states of ECMA-48:
+//! ground, escape, control sequence (CSI), operating system command (OSC) and device control string (DCS).
+
+use super::{Action, Graphic};
+use style::{Attribute, Color, UnderlineStyle};
+use terminal::ClearType;
+
+use std::str;
+
+/// The state the parser is in, it depends on the bytes that are parsed before.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    Csi,
+    /// An invalid control sequence, everything up to the end of it is ignored.
+    CsiIgnore,
+    Osc,
+    Dcs,
+    /// An start of string, privacy message or application program command, everything up to the string terminator is ignored.
+    IgnoreString,
+}
+
+/// An parser that turns bytes into `Action`s.
+///
+/// The bytes can be given in parts, an escape sequence or UTF-8 character that is not complete yet is parsed with the next bytes.
+///
+/// #Example
+///
+/// ```rust
+/// extern crate crossterm;
+///
+/// use self::crossterm::vt::{Action, Parser};
+///
+/// let mut parser = Parser::new();
+///
+/// assert_eq!(parser.advance(b"\x1B[1"), vec![]);
+/// assert_eq!(parser.advance(b"0A"), vec![Action::CursorUp(10)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Parser {
+    state: State,
+    /// The text that is parsed but not returned as action yet.
+    text: String,
+    /// The bytes of an UTF-8 character that is not complete yet.
+    utf8: Vec<u8>,
+    private: Option<u8>,
+    params: Vec<u16>,
+    /// Whether the parameter at the same index is an sub-parameter of the one before it, separated by `:`.
+    subparams: Vec<bool>,
+    /// Whether the parameter that is being parsed is an sub-parameter.
+    subparam: bool,
+    /// The parameter that is being parsed, `None` when no digit of it is parsed yet.
+    param: Option<u16>,
+    intermediates: Vec<u8>,
+    /// The content of an operating system command or device control string.
+    string: Vec<u8>,
+}
+
+impl Parser {
+    /// Create an parser that starts in the ground state.
+    pub fn new() -> Parser {
+        Parser {
+            state: State::Ground,
+            text: String::new(),
+            utf8: Vec::new(),
+            private: None,
+            params: Vec::new(),
+            subparams: Vec::new(),
+            subparam: false,
+            param: None,
+            intermediates: Vec::new(),
+            string: Vec::new(),
+        }
+    }
+
+    /// Parse the given bytes and get the actions that are completed by them.
+    pub fn advance(&mut self, bytes: &[u8]) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        for &byte in bytes {
+            self.advance_byte(byte, &mut actions);
+        }
+
+        self.flush_text(&mut actions);
+        actions
+    }
+
+    fn advance_byte(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        // an UTF-8 character can only be continued in the ground state.
+        if !self.utf8.is_empty() && !is_continuation(byte) {
+            self.utf8.clear();
+            self.text.push('\u{FFFD}');
+        }
+
+        match (self.state, byte) {
+            // these bytes are handled the same in all states.
+            (State::Osc, 0x07) => self.dispatch_osc(actions),
+            (State::Osc, 0x1B) => {
+                self.dispatch_osc(actions);
+                self.enter(State::Escape);
+            }
+            (State::Dcs, 0x1B) => {
+                self.dispatch_dcs(actions);
+                self.enter(State::Escape);
+            }
+            (_, 0x18) | (_, 0x1A) => self.state = State::Ground,
+            (_, 0x1B) => self.enter(State::Escape),
+
+            (State::Ground, _) => self.ground(byte, actions),
+            (State::Escape, _) => self.escape(byte, actions),
+            (State::Csi, _) => self.csi(byte, actions),
+            (State::CsiIgnore, _) => match byte {
+                0x00..=0x1F => self.execute(byte, actions),
+                0x40..=0x7E => self.state = State::Ground,
+                _ => {}
+            },
+            (State::Osc, 0x00..=0x1F) => {}
+            (State::Osc, _) | (State::Dcs, _) => self.string.push(byte),
+            (State::IgnoreString, _) => {}
+        }
+    }
+
+    /// Start parsing an new sequence in the given state.
+    fn enter(&mut self, state: State) {
+        self.state = state;
+        self.private = None;
+        self.params.clear();
+        self.subparams.clear();
+        self.subparam = false;
+        self.param = None;
+        self.intermediates.clear();
+        self.string.clear();
+    }
+
+    fn ground(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        match byte {
+            0x00..=0x1F => self.execute(byte, actions),
+            0x7F => {}
+            0x20..=0x7E => self.text.push(byte as char),
+            _ => self.utf8_byte(byte),
+        }
+    }
+
+    fn utf8_byte(&mut self, byte: u8) {
+        if self.utf8.is_empty() && utf8_len(byte).is_none() {
+            self.text.push('\u{FFFD}');
+            return;
+        }
+
+        self.utf8.push(byte);
+
+        if Some(self.utf8.len()) == utf8_len(self.utf8[0]) {
+            match str::from_utf8(&self.utf8) {
+                Ok(ch) => self.text.push_str(ch),
+                Err(_) => self.text.push('\u{FFFD}'),
+            }
+            self.utf8.clear();
+        }
+    }
+
+    /// Handle an control character.
+    fn execute(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        let action = match byte {
+            b'\n' | 0x0B | 0x0C => Action::LineFeed,
+            b'\r' => Action::CarriageReturn,
+            0x08 => Action::Backspace,
+            b'\t' => Action::Tab,
+            0x07 => Action::Bell,
+            _ => Action::Control(byte),
+        };
+
+        self.push(action, actions);
+    }
+
+    fn escape(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        match byte {
+            0x00..=0x1F => self.execute(byte, actions),
+            0x20..=0x2F => self.intermediates.push(byte),
+            b'[' if self.intermediates.is_empty() => self.enter(State::Csi),
+            b']' if self.intermediates.is_empty() => self.enter(State::Osc),
+            b'P' if self.intermediates.is_empty() => self.enter(State::Dcs),
+            b'X' | b'^' | b'_' if self.intermediates.is_empty() => self.enter(State::IgnoreString),
+            0x30..=0x7E => {
+                self.state = State::Ground;
+
+                let action = match (self.intermediates.is_empty(), byte) {
+                    (true, b'7') => Action::SaveCursor,
+                    (true, b'8') => Action::RestoreCursor,
+                    (true, b'D') => Action::Index,
+                    (true, b'E') => Action::CursorNextLine(1),
+                    (true, b'M') => Action::ReverseIndex,
+                    (true, b'c') => Action::Reset,
+                    // the string terminator, the string it ends is already handled.
+                    (true, b'\\') => return,
+                    _ => Action::Escape {
+                        intermediates: self.intermediates.clone(),
+                        action: byte as char,
+                    },
+                };
+
+                self.push(action, actions);
+            }
+            _ => {}
+        }
+    }
+
+    fn csi(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        match byte {
+            0x00..=0x1F => self.execute(byte, actions),
+            b'0'..=b'9' if self.intermediates.is_empty() => {
+                let digit = u16::from(byte - b'0');
+                let param = self.param.unwrap_or(0);
+                self.param = Some(param.saturating_mul(10).saturating_add(digit));
+            }
+            b';' | b':' if self.intermediates.is_empty() => {
+                let param = self.param.take().unwrap_or(0);
+                self.push_param(param);
+                self.subparam = byte == b':';
+            }
+            b'<'..=b'?'
+                if self.params.is_empty() && self.param.is_none() && self.private.is_none() =>
+            {
+                self.private = Some(byte);
+            }
+            0x20..=0x2F => self.intermediates.push(byte),
+            0x40..=0x7E => {
+                if let Some(param) = self.param.take() {
+                    self.push_param(param);
+                }
+                self.state = State::Ground;
+
+                let action = self.csi_action(byte as char);
+                self.push(action, actions);
+            }
+            0x7F => {}
+            _ => self.state = State::CsiIgnore,
+        }
+    }
+
+    fn push_param(&mut self, param: u16) {
+        self.params.push(param);
+        self.subparams.push(self.subparam);
+    }
+
+    /// Get the parameter at the given index, the default is used when it is missing or 0.
+    fn param(&self, index: usize, default: u16) -> u16 {
+        match self.params.get(index) {
+            Some(&param) if param != 0 => param,
+            _ => default,
+        }
+    }
+
+    fn csi_action(&self, action: char) -> Action {
+        let count = self.param(0, 1);
+
+        match (self.private, self.intermediates.is_empty(), action) {
+            (None, true, 'A') => Action::CursorUp(count),
+            (None, true, 'B') => Action::CursorDown(count),
+            (None, true, 'C') => Action::CursorForward(count),
+            (None, true, 'D') => Action::CursorBack(count),
+            (None, true, 'E') => Action::CursorNextLine(count),
+            (None, true, 'F') => Action::CursorPreviousLine(count),
+            (None, true, 'G') | (None, true, '`') => Action::CursorColumn(count - 1),
+            (None, true, 'd') => Action::CursorRow(count - 1),
+            (None, true, 'H') | (None, true, 'f') => {
+                Action::CursorGoto(self.param(1, 1) - 1, self.param(0, 1) - 1)
+            }
+            (None, true, 's') if self.params.is_empty() => Action::SaveCursor,
+            (None, true, 'u') if self.params.is_empty() => Action::RestoreCursor,
+            (None, true, 'n') if self.param(0, 0) == 6 => Action::RequestCursorPosition,
+            (None, true, 'J') if self.param(0, 0) <= 3 => {
+                Action::EraseDisplay(match self.param(0, 0) {
+                    0 => ClearType::FromCursorDown,
+                    1 => ClearType::FromCursorUp,
+                    _ => ClearType::All,
+                })
+            }
+            (None, true, 'K') if self.param(0, 0) <= 2 => {
+                Action::EraseLine(match self.param(0, 0) {
+                    0 => ClearType::UntilNewLine,
+                    1 => ClearType::FromCursorUp,
+                    _ => ClearType::CurrentLine,
+                })
+            }
+            (None, true, 'S') => Action::ScrollUp(count),
+            (None, true, 'T') => Action::ScrollDown(count),
+            (None, true, 't') if self.param(0, 0) == 8 && self.params.len() == 3 => {
+                Action::SetSize(self.param(1, 0), self.param(2, 0))
+            }
+            (None, true, 'm') => Action::SetGraphics(graphics(&self.params, &self.subparams)),
+            (Some(b'?'), true, 'h') | (Some(b'?'), true, 'l') if self.params.len() == 1 => {
+                let enabled = action == 'h';
+
+                match self.params[0] {
+                    25 => Action::ShowCursor(enabled),
+                    1049 => Action::AlternateScreen(enabled),
+                    mode => Action::SetMode {
+                        private: true,
+                        mode,
+                        enabled,
+                    },
+                }
+            }
+            (None, true, 'h') | (None, true, 'l') if self.params.len() == 1 => Action::SetMode {
+                private: false,
+                mode: self.params[0],
+                enabled: action == 'h',
+            },
+            _ => Action::Csi {
+                private: self.private,
+                params: self.params.clone(),
+                intermediates: self.intermediates.clone(),
+                action,
+            },
+        }
+    }
+
+    fn dispatch_osc(&mut self, actions: &mut Vec<Action>) {
+        self.state = State::Ground;
+
+        let action = {
+            let string = String::from_utf8_lossy(&self.string);
+
+            if string.starts_with("0;") || string.starts_with("2;") {
+                Action::SetTitle(string[2..].to_string())
+            } else {
+                Action::OperatingSystemCommand(self.string.clone())
+            }
+        };
+
+        self.push(action, actions);
+    }
+
+    fn dispatch_dcs(&mut self, actions: &mut Vec<Action>) {
+        let action = Action::DeviceControl(self.string.clone());
+        self.push(action, actions);
+    }
+
+    /// Add the given action after the text that is parsed before it.
+    fn push(&mut self, action: Action, actions: &mut Vec<Action>) {
+        self.flush_text(actions);
+        actions.push(action);
+    }
+
+    fn flush_text(&mut self, actions: &mut Vec<Action>) {
+        if !self.text.is_empty() {
+            actions.push(Action::Print(self.text.clone()));
+            self.text.clear();
+        }
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Get the length of the UTF-8 character that starts with the given byte, `None` when it can not start an character.
+fn utf8_len(byte: u8) -> Option<usize> {
+    match byte {
+        0xC2..=0xDF => Some(2),
+        0xE0..=0xEF => Some(3),
+        0xF0..=0xF4 => Some(4),
+        _ => None,
+    }
+}
+
+/// Get the color with the given index of the 256 colors, `None` when the index is not an color.
+fn indexed_color(index: u16) -> Option<Color> {
+    if index > 255 {
+        return None;
+    }
+
+    Some(Color::from_ansi_index(index as u8))
+}
+
+/// Get the color of an `38` or `48` parameter from the parameters after it,
+/// returns the color and the number of parameters that belong to it.
+fn extended_color(params: &[u16]) -> (Option<Color>, usize) {
+    match params.first() {
+        Some(&5) if params.len() >= 2 => (indexed_color(params[1]), 2),
+        Some(&2) if params.len() >= 4 && params[1..4].iter().all(|&value| value <= 255) => (
+            Some(Color::Rgb {
+                r: params[1] as u8,
+                g: params[2] as u8,
+                b: params[3] as u8,
+            }),
+            4,
+        ),
+        Some(&2) if params.len() >= 4 => (None, 4),
+        _ => (None, params.len()),
+    }
+}
+
+/// Get the color of an `38`, `48` or `58` parameter from its sub-parameters, like `38:5:n`, `38:2:r:g:b`
+/// or `38:2:id:r:g:b` with an color space id.
+fn subparameter_color(subparams: &[u16]) -> Option<Color> {
+    match *subparams {
+        [5, index, ..] => indexed_color(index),
+        [2, _, r, g, b, ..] | [2, r, g, b] if r <= 255 && g <= 255 && b <= 255 => {
+            Some(Color::Rgb {
+                r: r as u8,
+                g: g as u8,
+                b: b as u8,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Get the graphic of the `4:n` underline style parameter.
+fn underline_style(style: u16) -> Graphic {
+    match style {
+        0 => Graphic::Attribute(Attribute::NoUnderline),
+        1 => Graphic::UnderlineStyle(UnderlineStyle::Single),
+        2 => Graphic::UnderlineStyle(UnderlineStyle::Double),
+        3 => Graphic::UnderlineStyle(UnderlineStyle::Curly),
+        4 => Graphic::UnderlineStyle(UnderlineStyle::Dotted),
+        5 => Graphic::UnderlineStyle(UnderlineStyle::Dashed),
+        _ => Graphic::Unknown(4),
+    }
+}
+
+/// Get the graphics of the parameters of an `m` control sequence.
+fn graphics(params: &[u16], subparams: &[bool]) -> Vec<Graphic> {
+    if params.is_empty() {
+        return vec![Graphic::Reset];
+    }
+
+    let mut graphics = Vec::new();
+    let mut index = 0;
+
+    while index < params.len() {
+        let param = params[index];
+        index += 1;
+
+        // the sub-parameters that are separated from the parameter with `:`.
+        let start = index;
+        while subparams.get(index) == Some(&true) {
+            index += 1;
+        }
+        let sub = &params[start..index];
+
+        let graphic = match param {
+            0 => Graphic::Reset,
+            1 => Graphic::Attribute(Attribute::Bold),
+            2 => Graphic::Attribute(Attribute::Dim),
+            3 => Graphic::Attribute(Attribute::Italic),
+            4 if !sub.is_empty() => underline_style(sub[0]),
+            4 => Graphic::Attribute(Attribute::Underlined),
+            5 => Graphic::Attribute(Attribute::SlowBlink),
+            6 => Graphic::Attribute(Attribute::RapidBlink),
+            7 => Graphic::Attribute(Attribute::Reverse),
+            8 => Graphic::Attribute(Attribute::Hidden),
+            9 => Graphic::Attribute(Attribute::CrossedOut),
+            21 => Graphic::Attribute(Attribute::DoubleUnderlined),
+            22 => Graphic::Attribute(Attribute::NoBold),
+            23 => Graphic::Attribute(Attribute::NoItalic),
+            24 => Graphic::Attribute(Attribute::NoUnderline),
+            25 => Graphic::Attribute(Attribute::NoBlink),
+            27 => Graphic::Attribute(Attribute::NoReverse),
+            28 => Graphic::Attribute(Attribute::NoHidden),
+            29 => Graphic::Attribute(Attribute::NotCrossedOut),
+            51 => Graphic::Attribute(Attribute::Framed),
+            52 => Graphic::Attribute(Attribute::Encircled),
+            53 => Graphic::Attribute(Attribute::Overlined),
+            54 => Graphic::Attribute(Attribute::NotFramedOrEncircled),
+            55 => Graphic::Attribute(Attribute::NotOverlined),
+            30..=37 => named(indexed_color(param - 30), Graphic::Foreground, param),
+            40..=47 => named(indexed_color(param - 40), Graphic::Background, param),
+            90..=97 => named(indexed_color(param - 90 + 8), Graphic::Foreground, param),
+            100..=107 => named(indexed_color(param - 100 + 8), Graphic::Background, param),
+            38 | 48 | 58 => {
+                let color = if sub.is_empty() {
+                    let (color, len) = extended_color(&params[index..]);
+                    index += len;
+                    color
+                } else {
+                    subparameter_color(sub)
+                };
+
+                match (color, param) {
+                    (Some(color), 38) => Graphic::Foreground(color),
+                    (Some(color), 48) => Graphic::Background(color),
+                    (Some(color), _) => Graphic::UnderlineColor(color),
+                    (None, _) => Graphic::Unknown(param),
+                }
+            }
+            39 => Graphic::DefaultForeground,
+            49 => Graphic::DefaultBackground,
+            59 => Graphic::DefaultUnderlineColor,
+            _ => Graphic::Unknown(param),
+        };
+
+        graphics.push(graphic);
+    }
+
+    graphics
+}
+
+/// Get the graphic for the given color, `Graphic::Unknown` with the given parameter when there is no color.
+fn named<F: Fn(Color) -> Graphic>(color: Option<Color>, graphic: F, param: u16) -> Graphic {
+    match color {
+        Some(color) => graphic(color),
+        None => Graphic::Unknown(param),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Parser;
+    use style::{Attribute, Color, UnderlineStyle};
+    use terminal::ClearType;
+    use vt::{Action, Graphic};
+
+    fn print(text: &str) -> Action {
+        Action::Print(text.to_string())
+    }
+
+    #[test]
+    fn parses_an_utf8_character_split_across_advances() {
+        let bytes = "a\u{754C}b".as_bytes();
+        let mut parser = Parser::new();
+
+        assert_eq!(parser.advance(&bytes[..2]), vec![print("a")]);
+        assert_eq!(parser.advance(&bytes[2..3]), vec![]);
+        assert_eq!(parser.advance(&bytes[3..]), vec![print("\u{754C}b")]);
+    }
+
+    #[test]
+    fn replaces_invalid_utf8() {
+        let mut parser = Parser::new();
+
+        assert_eq!(parser.advance(b"\xFFa"), vec![print("\u{FFFD}a")]);
+        // the character is cut off by the escape sequence.
+        assert_eq!(
+            parser.advance(b"\xE7\x95\x1B[A"),
+            vec![print("\u{FFFD}"), Action::CursorUp(1)]
+        );
+    }
+
+    #[test]
+    fn parses_an_escape_sequence_split_across_advances() {
+        let mut parser = Parser::new();
+
+        assert_eq!(parser.advance(b"ab\x1B"), vec![print("ab")]);
+        assert_eq!(parser.advance(b"["), vec![]);
+        assert_eq!(parser.advance(b"3;"), vec![]);
+        assert_eq!(
+            parser.advance(b"4Hc"),
+            vec![Action::CursorGoto(3, 2), print("c")]
+        );
+    }
+
+    #[test]
+    fn waits_for_the_final_byte_of_an_control_sequence() {
+        let mut parser = Parser::new();
+
+        assert_eq!(parser.advance(b"\x1B[12;40"), vec![]);
+        assert_eq!(
+            parser.advance(b"\x1B[2J"),
+            vec![Action::EraseDisplay(ClearType::All)]
+        );
+        assert_eq!(parser.advance(b"x"), vec![print("x")]);
+    }
+
+    #[test]
+    fn executes_control_characters_inside_an_control_sequence() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B[1\r0B"),
+            vec![Action::CarriageReturn, Action::CursorDown(10)]
+        );
+    }
+
+    #[test]
+    fn cancels_an_control_sequence() {
+        assert_eq!(Parser::new().advance(b"\x1B[12\x18A"), vec![print("A")]);
+    }
+
+    #[test]
+    fn ignores_an_invalid_control_sequence() {
+        assert_eq!(Parser::new().advance(b"\x1B[1\x80;2Hx"), vec![print("x")]);
+    }
+
+    #[test]
+    fn parses_private_modes() {
+        let mut parser = Parser::new();
+
+        assert_eq!(
+            parser.advance(b"\x1B[?25l"),
+            vec![Action::ShowCursor(false)]
+        );
+        assert_eq!(
+            parser.advance(b"\x1B[?1049h"),
+            vec![Action::AlternateScreen(true)]
+        );
+        assert_eq!(
+            parser.advance(b"\x1B[?1000h"),
+            vec![Action::SetMode {
+                private: true,
+                mode: 1000,
+                enabled: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_an_operating_system_command_ending_with_bel() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B]0;title\x07x"),
+            vec![Action::SetTitle("title".to_string()), print("x")]
+        );
+    }
+
+    #[test]
+    fn parses_an_operating_system_command_ending_with_st() {
+        let mut parser = Parser::new();
+
+        assert_eq!(parser.advance(b"\x1B]2;ti"), vec![]);
+        assert_eq!(
+            parser.advance(b"tle\x1B"),
+            vec![Action::SetTitle("title".to_string())]
+        );
+        assert_eq!(parser.advance(b"\\x"), vec![print("x")]);
+        assert_eq!(
+            parser.advance(b"\x1B]8;;link\x1B\\"),
+            vec![Action::OperatingSystemCommand(b"8;;link".to_vec())]
+        );
+    }
+
+    #[test]
+    fn parses_an_device_control_string() {
+        assert_eq!(
+            Parser::new().advance(b"\x1BPdata\x1B\\"),
+            vec![Action::DeviceControl(b"data".to_vec())]
+        );
+    }
+
+    #[test]
+    fn ignores_the_other_strings() {
+        assert_eq!(Parser::new().advance(b"\x1B_apc\x1B\\x"), vec![print("x")]);
+    }
+
+    #[test]
+    fn parses_graphics() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B[m"),
+            vec![Action::SetGraphics(vec![Graphic::Reset])]
+        );
+        assert_eq!(
+            Parser::new().advance(b"\x1B[1;31;102;39m"),
+            vec![Action::SetGraphics(vec![
+                Graphic::Attribute(Attribute::Bold),
+                Graphic::Foreground(Color::DarkRed),
+                Graphic::Background(Color::Green),
+                Graphic::DefaultForeground,
+            ])]
+        );
+    }
+
+    #[test]
+    fn parses_semicolon_separated_colors() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B[38;5;200;48;2;1;2;3;4m"),
+            vec![Action::SetGraphics(vec![
+                Graphic::Foreground(Color::AnsiValue(200)),
+                Graphic::Background(Color::Rgb { r: 1, g: 2, b: 3 }),
+                Graphic::Attribute(Attribute::Underlined),
+            ])]
+        );
+    }
+
+    #[test]
+    fn parses_colon_separated_colors() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B[38:5:200;48:2::1:2:3;58:2:1:2:3m"),
+            vec![Action::SetGraphics(vec![
+                Graphic::Foreground(Color::AnsiValue(200)),
+                Graphic::Background(Color::Rgb { r: 1, g: 2, b: 3 }),
+                Graphic::UnderlineColor(Color::Rgb { r: 1, g: 2, b: 3 }),
+            ])]
+        );
+    }
+
+    #[test]
+    fn parses_underline_styles() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B[4:3;4:0;4:9m"),
+            vec![Action::SetGraphics(vec![
+                Graphic::UnderlineStyle(UnderlineStyle::Curly),
+                Graphic::Attribute(Attribute::NoUnderline),
+                Graphic::Unknown(4),
+            ])]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_colors() {
+        assert_eq!(
+            Parser::new().advance(b"\x1B[38;5;256;48;2;256;0;0;1m"),
+            vec![Action::SetGraphics(vec![
+                Graphic::Unknown(38),
+                Graphic::Unknown(48),
+                Graphic::Attribute(Attribute::Bold),
+            ])]
+        );
+        assert_eq!(
+            Parser::new().advance(b"\x1B[38:2:256:0:0m"),
+            vec![Action::SetGraphics(vec![Graphic::Unknown(38)])]
+        );
+    }
+}